@@ -1,6 +1,11 @@
 //! The boltz module contains various traits and functions for solving different
 //! forms of the Boltzmann equation:
 //!
+//! # `boltz::traits`
+//! This module contains the trait `DarkMatterModel` which every model
+//! implements. Any type implementing it automatically implements the solver
+//! traits `FullBoltzmann`, `SimpleBoltzmann` and `CoupledBoltzmann`.
+//!
 //! # `boltz::full`
 //! This module contains the trait `FullBoltzmann` and allows any type that
 //! implements it to solve the full Boltzmann equation for the DM phase-space
@@ -14,16 +19,66 @@
 //! # `boltz::simple`
 //! This module contains the trait `SimpleBoltzmann` and allows any type that
 //! implements it to solve the standard Boltzmann equation for the DM comoving
-//! number density.
+//! number density, including semi-annihilations chi+chi -> chi+φ and
+//! 3 -> 2 processes chi+chi+chi -> chi+chi when the model provides them.
+//!
+//! # `boltz::asymmetric`
+//! This module solves for the comoving number densities of a Dirac DM particle
+//! and its antiparticle with a conserved asymmetry, either for the yields or
+//! for the two phase-space distributions, and reports the symmetric and
+//! asymmetric components of the relic density.
+//!
+//! # `boltz::cosmology`
+//! This module contains the trait `Cosmology` which supplies the expansion
+//! history (H(T), s(T), g_eff, h_eff) used by all the solvers.
+//!
+//! # `boltz::decay`
+//! This module contains the decay source terms used by the solvers: two-body
+//! decays of heavier states into the DM, with the DM momentum spectrum in the
+//! full solver and the thermally averaged width in the simple solver, and
+//! decays of a long-lived DM.
+//!
+//! # `boltz::diagnostics`
+//! This module checks solutions of the full Boltzmann equation for physical
+//! consistency: number density changes against the integrated annihilation
+//! rate, energy transfer to the bath and tight coupling to `feq`.
+//!
+//! # `boltz::kinetic_decoupling`
+//! This module computes the kinetic decoupling temperature of the DM for any
+//! type implementing `FullBoltzmann`.
+//!
+//! # `boltz::protohalo`
+//! This module computes the free-streaming length and the smallest protohalo
+//! mass from the DM phase-space distribution or kinetic decoupling
+//! temperature.
+//!
+//! # `boltz::reheating`
+//! This module solves for the DM comoving number density while a decaying
+//! modulus reheats the SM bath, tracking the modulus energy density and the
+//! SM entropy alongside the DM.
 
+pub mod asymmetric;
+pub mod cosmology;
 pub mod coupled;
+pub mod decay;
+pub mod diagnostics;
 pub mod full;
 pub mod helper;
+pub mod kinetic_decoupling;
+pub mod protohalo;
+pub mod reheating;
 pub mod simple;
 pub mod traits;
 
+pub use asymmetric::*;
+pub use cosmology::*;
 pub use coupled::*;
+pub use decay::*;
+pub use diagnostics::*;
 pub use full::*;
 pub use helper::*;
+pub use kinetic_decoupling::*;
+pub use protohalo::*;
+pub use reheating::*;
 pub use simple::*;
 pub use traits::*;
@@ -0,0 +1,289 @@
+//! Freeze-out of Dirac DM with a particle-antiparticle asymmetry. The DM and
+//! its antiparticle only annihilate with each other, chi+chibar -> SM, so
+//! the asymmetry η = Y_χ - Y_χ̄ is conserved (up to entropy injection) and
+//!     dY_χ̄/dx = -s<σv>/(Hx) (Y_χ Y_χ̄ - Y_eq^2),
+//! where Y_eq is the equilibrium yield of a single species with vanishing
+//! chemical potential. Once the symmetric component 2 Y_χ̄ has annihilated
+//! away, the relic density is set by η. The solvers here report the
+//! symmetric and asymmetric components of the relic density separately.
+//! Semi-annihilations, 3 -> 2 processes, self-scattering and decays
+//! (`decay_width` and `decays`) are not included.
+
+use crate::utils::derivatives::*;
+use cyphus_diffeq::prelude::*;
+use haliax_constants::prelude::*;
+use ndarray::prelude::*;
+use ndarray::Zip;
+use std::f64::consts::PI;
+
+use super::cosmology::Cosmology;
+use super::full::{compute_dfi, compute_j_dfi, compute_j_partner_dfi, momentum_grid, StepContext};
+use super::helper::bath_temperature;
+use super::traits::{FullBoltzmann, SimpleBoltzmann};
+
+/// Result of integrating the Boltzmann equations for asymmetric DM.
+pub struct AsymmetricSolution {
+    /// Solution of the underlying ODE. For `integrate_asymmetric_boltzmann`
+    /// this is (ln Y_χ̄, ln(η / η_init)) vs. ln(x), and for
+    /// `integrate_asymmetric_full_boltzmann` the concatenated phase-space
+    /// distributions (f_χ, f_χ̄) vs. x.
+    pub sol: OdeSolution,
+    /// Final symmetric component of the comoving number density, 2 Y_χ̄.
+    pub y_sym: f64,
+    /// Final asymmetric component of the comoving number density, |Y_χ - Y_χ̄|.
+    pub y_asym: f64,
+    /// Relic density Ω h^2 of the symmetric component.
+    pub omega_sym: f64,
+    /// Relic density Ω h^2 of the asymmetric component.
+    pub omega_asym: f64,
+}
+
+impl AsymmetricSolution {
+    fn new(sol: OdeSolution, mx: f64, y_sym: f64, y_asym: f64) -> AsymmetricSolution {
+        AsymmetricSolution {
+            sol,
+            y_sym,
+            y_asym,
+            omega_sym: y_sym * mx * S_TODAY / RHO_CRIT,
+            omega_asym: y_asym * mx * S_TODAY / RHO_CRIT,
+        }
+    }
+    /// Total relic density Ω h^2 of the DM and its antiparticle.
+    pub fn omega(&self) -> f64 {
+        self.omega_sym + self.omega_asym
+    }
+    /// Fraction of the relic density made up by the symmetric component.
+    pub fn symmetric_fraction(&self) -> f64 {
+        self.omega_sym / self.omega()
+    }
+}
+
+/// Compute the yields (Y_χ, Y_χ̄) in chemical equilibrium with the bath given
+/// the single-species equilibrium yield `yeq` and the asymmetry `eta`,
+/// i.e. Y_χ - Y_χ̄ = η and Y_χ Y_χ̄ = Y_eq^2.
+pub fn asymmetric_equilibrium_yields(yeq: f64, eta: f64) -> (f64, f64) {
+    let ybar = 2.0 * yeq * yeq / (eta + (eta * eta + 4.0 * yeq * yeq).sqrt());
+    (ybar + eta, ybar)
+}
+
+/// Integrate the Boltzmann equation for the antiparticle yield Y_χ̄ of a
+/// Dirac DM model with initial asymmetry `eta` = Y_χ - Y_χ̄ from x = `xmin`
+/// to `xmax`. The model's cross section is taken to be that of
+/// chi+chibar -> SM and `g` counts the degrees of freedom of a single
+/// species. The asymmetry is evolved alongside Y_χ̄ so that it is diluted
+/// correctly in non-standard cosmologies. `eta` = 0 gives the symmetric
+/// case, and a negative `eta` just exchanges the roles of χ and χ̄.
+pub fn integrate_asymmetric_boltzmann<T: SimpleBoltzmann, C: Cosmology>(
+    model: T,
+    cosmo: &C,
+    eta: f64,
+    xmin: f64,
+    xmax: f64,
+) -> AsymmetricSolution {
+    let eta = eta.abs();
+    let mx = model.mass();
+    let dudt = |mut dw: ArrayViewMut1<f64>, w: ArrayView1<f64>, logx: f64, p: &T| {
+        let x: f64 = logx.exp();
+        let temp: f64 = mx / x;
+        let tb: f64 = bath_temperature(cosmo, temp);
+        let s: f64 = cosmo.entropy_density(temp);
+        let n = p.equilibrium_density(tb);
+        let weq: f64 = (n / s).ln();
+
+        let pf: f64 = -s / cosmo.hubblet(temp);
+        let sigmav: f64 = p.thermal_cross_section(mx / tb);
+        let dil: f64 = 3.0 + cosmo.dlnh_dlnt(temp) - 3.0 * cosmo.dlna_dlnx(temp);
+
+        // d ln(Y_χ̄) / dlogx with Y_χ = Y_χ̄ + η
+        dw[0] = pf * sigmav * (w[0].exp() + eta * w[1].exp() - (2.0 * weq - w[0]).exp()) + dil;
+        // d ln(η / η_init) / dlogx
+        dw[1] = dil;
+    };
+    let dfdu = |mut df: ArrayViewMut2<f64>, w: ArrayView1<f64>, logx: f64, p: &T| {
+        let x: f64 = logx.exp();
+        let temp: f64 = mx / x;
+        let tb: f64 = bath_temperature(cosmo, temp);
+        let s: f64 = cosmo.entropy_density(temp);
+        let n = p.equilibrium_density(tb);
+        let weq: f64 = (n / s).ln();
+
+        let pf: f64 = -s / cosmo.hubblet(temp);
+        let sigmav: f64 = p.thermal_cross_section(mx / tb);
+
+        df.fill(0.0);
+        df[[0, 0]] = pf * sigmav * (w[0].exp() + (2.0 * weq - w[0]).exp());
+        df[[0, 1]] = pf * sigmav * eta * w[1].exp();
+    };
+    let temp = mx / xmin;
+    let tb = bath_temperature(cosmo, temp);
+    let yeq = model.equilibrium_density(tb) / cosmo.entropy_density(temp);
+    let (_, ybar) = asymmetric_equilibrium_yields(yeq, eta);
+    let uinit = array![ybar.ln(), 0.0];
+    let tspan = (xmin.ln(), xmax.ln());
+
+    let mut integrator = OdeIntegratorBuilder::default(&dudt, uinit, tspan, Radau5, model)
+        .dfdu(&dfdu)
+        .reltol(1e-7)
+        .abstol(1e-7)
+        .build();
+    integrator.integrate();
+    let sol = integrator.sol;
+
+    let uf = &sol.us[sol.us.len() - 1];
+    let (y_sym, y_asym) = (2.0 * uf[0].exp(), eta * uf[1].exp());
+    AsymmetricSolution::new(sol, mx, y_sym, y_asym)
+}
+
+/// Compute the comoving number density Y = n / s of a species with `g`
+/// degrees of freedom and phase-space distribution `f` on the momentum grid
+/// `qs` at x = m / `temp`.
+pub fn yield_from_distribution<C: Cosmology>(
+    cosmo: &C,
+    temp: f64,
+    g: f64,
+    f: ArrayView1<f64>,
+    qs: ArrayView1<f64>,
+) -> f64 {
+    let n = qs.len();
+    let dq = qs[1] - qs[0];
+    let mut int = 0.0;
+    for k in 0..n {
+        let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+        int += wgt * qs[k] * qs[k] * f[k];
+    }
+    g * temp.powi(3) * int * dq / (2.0 * PI * PI * cosmo.entropy_density(temp))
+}
+
+/// Integrate the full Boltzmann equations for the phase-space distributions
+/// of a Dirac DM particle and its antiparticle with initial asymmetry `eta`
+/// = Y_χ - Y_χ̄. Both distributions scatter elastically off the bath and
+/// annihilate only with each other. They start in kinetic equilibrium with
+/// chemical potentials ±μ fixed by `eta`. As for
+/// `integrate_asymmetric_boltzmann`, only |`eta`| matters.
+pub fn integrate_asymmetric_full_boltzmann<T: FullBoltzmann + Sync, C: Cosmology + Sync>(
+    model: T,
+    cosmo: &C,
+    eta: f64,
+    n: usize,
+    xspan: (f64, f64),
+) -> AsymmetricSolution {
+    let eta = eta.abs();
+    let qs = momentum_grid(n);
+    let dq = qs[1] - qs[0];
+
+    let mx = model.mass();
+    let g = model.g();
+    let j_df = jac_first_deriv_vec(n, dq);
+    let j_d2f = jac_second_deriv_vec(n, dq);
+
+    // The state is the concatenation (f_χ, f_χ̄).
+    let dudt = |deriv: ArrayViewMut1<f64>, u: ArrayView1<f64>, x: f64, p: &T| {
+        let ctx = StepContext::new(p, cosmo, x, qs.view());
+        let (f, fbar) = (u.slice(s![..n]), u.slice(s![n..]));
+        let (df, d2f) = (first_deriv_vec(f, dq), second_deriv_vec(f, dq));
+        let (dfbar, d2fbar) = (first_deriv_vec(fbar, dq), second_deriv_vec(fbar, dq));
+
+        let (dchi, dchibar) = deriv.split_at(Axis(0), n);
+        Zip::indexed(dchi).par_apply(|i, d| {
+            *d = compute_dfi(i, f, fbar, df[i], d2f[i], &ctx, p);
+        });
+        Zip::indexed(dchibar).par_apply(|i, d| {
+            *d = compute_dfi(i, fbar, f, dfbar[i], d2fbar[i], &ctx, p);
+        });
+    };
+
+    // Each distribution only annihilates with the other, so the off-diagonal
+    // blocks only contain the partner derivatives.
+    let dfdu = |jac: ArrayViewMut2<f64>, u: ArrayView1<f64>, x: f64, p: &T| {
+        let ctx = StepContext::new(p, cosmo, x, qs.view());
+        let (f, fbar) = (u.slice(s![..n]), u.slice(s![n..]));
+        Zip::indexed(jac).par_apply(|(a, b), d| {
+            let (i, j) = (a % n, b % n);
+            let (own, partner) = if a < n { (f, fbar) } else { (fbar, f) };
+            *d = if a / n == b / n {
+                compute_j_dfi(i, j, partner, j_df[[i, j]], j_d2f[[i, j]], &ctx, p)
+            } else {
+                compute_j_partner_dfi(i, j, own, &ctx, p)
+            };
+        });
+    };
+
+    // Start in kinetic equilibrium with f_χ = r f_eq and f_χ̄ = f_eq / r,
+    // where r = exp(μ / T) is fixed by the asymmetry.
+    let temp = mx / xspan.0;
+    let xi = cosmo.xi(temp);
+    let feq = qs.mapv(|q| model.feq(xspan.0 / xi, q / xi));
+    let yeq = yield_from_distribution(cosmo, temp, g, feq.view(), qs.view());
+    let (ychi, _) = asymmetric_equilibrium_yields(yeq, eta);
+    let r = ychi / yeq;
+    let mut finit = Array1::<f64>::zeros(2 * n);
+    finit.slice_mut(s![..n]).assign(&(&feq * r));
+    finit.slice_mut(s![n..]).assign(&(&feq / r));
+
+    let mut integrator = OdeIntegratorBuilder::default(&dudt, finit, xspan, Radau5, model)
+        .abstol(1e-100)
+        .reltol(1e-6)
+        .dfdu(&dfdu)
+        .build();
+    integrator.integrate();
+    let sol = integrator.sol;
+
+    let tf = mx / sol.ts[sol.ts.len() - 1];
+    let uf = &sol.us[sol.us.len() - 1];
+    let ychi = yield_from_distribution(cosmo, tf, g, uf.slice(s![..n]), qs.view());
+    let ybar = yield_from_distribution(cosmo, tf, g, uf.slice(s![n..]), qs.view());
+    AsymmetricSolution::new(sol, mx, 2.0 * ybar, ychi - ybar)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::full::integrate_full_boltzmann;
+    use crate::boltz::simple::integrate_simple_boltzmann;
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+
+    fn model() -> ConstantCrossSection {
+        ConstantCrossSection::new(1e-8)
+    }
+
+    /// Without an asymmetry, the DM and its antiparticle each follow the
+    /// symmetric solution.
+    #[test]
+    fn test_vanishing_asymmetry() {
+        let cosmo = StandardCosmology;
+        let sol = integrate_simple_boltzmann(model(), &cosmo, 1.0, 1000.0);
+        let y = sol.us[sol.us.len() - 1][0].exp();
+        for &eta in [0.0, 1e-30].iter() {
+            let asym = integrate_asymmetric_boltzmann(model(), &cosmo, eta, 1.0, 1000.0);
+            assert!((asym.y_sym / (2.0 * y) - 1.0).abs() < 1e-3);
+            assert!(asym.symmetric_fraction() > 1.0 - 1e-6);
+            assert!((asym.y_asym - eta).abs() <= 1e-6 * eta);
+        }
+    }
+
+    /// A large asymmetry depletes the antiparticles and sets the relic density.
+    #[test]
+    fn test_large_asymmetry() {
+        let eta = 1e-10;
+        let asym = integrate_asymmetric_boltzmann(model(), &StandardCosmology, eta, 1.0, 1000.0);
+        let omega = eta * 100.0 * S_TODAY / RHO_CRIT;
+        assert!((asym.y_asym / eta - 1.0).abs() < 1e-6);
+        assert!((asym.omega_asym / omega - 1.0).abs() < 1e-6);
+        assert!(asym.symmetric_fraction() < 1e-3);
+    }
+
+    /// Without an asymmetry, the full Boltzmann equations give the same
+    /// symmetric yield as the symmetric full Boltzmann equation.
+    #[test]
+    fn test_vanishing_asymmetry_full() {
+        let cosmo = StandardCosmology;
+        let (n, xspan) = (30, (10.0, 50.0));
+        let sol = integrate_full_boltzmann(model(), &cosmo, n, xspan).sol;
+        let asym = integrate_asymmetric_full_boltzmann(model(), &cosmo, 0.0, n, xspan);
+        let qs = momentum_grid(n);
+        let f = &sol.us[sol.us.len() - 1];
+        let y = yield_from_distribution(&cosmo, 100.0 / xspan.1, 2.0, f.view(), qs.view());
+        assert!((asym.y_sym / (2.0 * y) - 1.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,278 @@
+//! This module contains the `Cosmology` trait, which provides the expansion
+//! history used by the Boltzmann solvers, along with a few implementations:
+//!
+//! - `StandardCosmology`: radiation domination with the SM d.o.f.,
+//! - `EarlyMatterDomination`: a matter-dominated era ending with reheating at
+//!   temperature `trh`, during which entropy is injected into the SM bath,
+//! - `Kination`: an era dominated by a fluid with w = 1 (ρ ~ a^-6),
+//! - `DarkRadiation`: the standard cosmology with an extra decoupled radiation
+//!   component parameterized by ΔN_eff,
+//! - `DarkBath`: the standard cosmology with a secluded dark radiation bath
+//!   at temperature T' = ξ T, which the DM is in contact with.
+
+use haliax_constants::cosmology::M_PLANK;
+use haliax_thermal_functions::prelude::*;
+use std::f64::consts::PI;
+
+pub trait Cosmology {
+    /// Effective number of d.o.f. stored in energy.
+    fn geff(&self, temp: f64) -> f64;
+    /// Effective number of d.o.f. stored in entropy.
+    fn heff(&self, temp: f64) -> f64;
+    /// Logarithmic derivative of `heff` with respect to temperature.
+    fn dlnh_dlnt(&self, temp: f64) -> f64;
+    /// Hubble rate at temperature `temp`.
+    fn hubble(&self, temp: f64) -> f64;
+    /// Entropy density of the SM bath.
+    fn entropy_density(&self, temp: f64) -> f64 {
+        2.0 * PI * PI / 45.0 * self.heff(temp) * temp.powi(3)
+    }
+    /// Compute -dln(a)/dln(T) = dln(a)/dln(x). If the SM entropy is conserved
+    /// this is 1 + dln(heff)/dln(T) / 3.
+    fn dlna_dlnx(&self, temp: f64) -> f64 {
+        1.0 + self.dlnh_dlnt(temp) / 3.0
+    }
+    /// Compute -dln(T)/dt = H / (dln(a)/dln(x)).
+    fn hubblet(&self, temp: f64) -> f64 {
+        self.hubble(temp) / self.dlna_dlnx(temp)
+    }
+    /// Compute dln(a)/dln(x) - 1, which controls the redshifting of q = p / T.
+    fn gefft(&self, temp: f64) -> f64 {
+        self.dlna_dlnx(temp) - 1.0
+    }
+    /// Ratio ξ = T' / T of the temperature of the bath the DM annihilates into
+    /// and scatters off of to the SM temperature.
+    fn xi(&self, _temp: f64) -> f64 {
+        1.0
+    }
+}
+
+/// Radiation dominated universe containing only the SM.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StandardCosmology;
+
+/// Early-matter-dominated universe in which a heavy field decays into the SM
+/// bath, reheating it at the temperature `trh`. The energy density of the
+/// field relative to radiation scales as (T / trh)^4 and the SM temperature
+/// scales as a^(-3/8) deep in the matter-dominated era.
+#[derive(Clone, Copy, Debug)]
+pub struct EarlyMatterDomination {
+    /// Reheating temperature.
+    pub trh: f64,
+}
+
+/// Universe with a kination era, where the energy density of a stiff fluid
+/// (ρ ~ a^-6) equals that of radiation at the temperature `tkin`.
+#[derive(Clone, Copy, Debug)]
+pub struct Kination {
+    /// Temperature at which the stiff fluid and radiation have equal energy
+    /// densities.
+    pub tkin: f64,
+}
+
+/// Standard cosmology with an extra, decoupled dark radiation component.
+#[derive(Clone, Copy, Debug)]
+pub struct DarkRadiation {
+    /// Contribution of the dark radiation to the effective number of
+    /// neutrinos.
+    pub delta_neff: f64,
+}
+
+/// Standard cosmology with an additional secluded bath of massless dark
+/// radiation. The dark and SM entropies are separately conserved, so that
+///     ξ(T) = ξ_ref (heff(T) / heff(T_ref))^(1/3).
+#[derive(Clone, Copy, Debug)]
+pub struct DarkBath {
+    /// Ratio T' / T at the reference temperature.
+    pub xi_ref: f64,
+    /// SM reference temperature at which ξ = `xi_ref`.
+    pub tref: f64,
+    /// Effective number of d.o.f. of the dark bath.
+    pub gdark: f64,
+}
+
+/// Hubble rate of a radiation-dominated universe with `geff` d.o.f.
+fn hubble_rad(temp: f64, geff: f64) -> f64 {
+    (4.0 * PI.powi(3) * geff / 45.0).sqrt() * temp * temp / M_PLANK
+}
+
+impl Cosmology for StandardCosmology {
+    fn geff(&self, temp: f64) -> f64 {
+        sm_geff(temp)
+    }
+    fn heff(&self, temp: f64) -> f64 {
+        sm_heff(temp)
+    }
+    fn dlnh_dlnt(&self, temp: f64) -> f64 {
+        3.0 * (sm_sqrt_gstar(temp) * sm_geff(temp).sqrt() / sm_heff(temp) - 1.0)
+    }
+    fn hubble(&self, temp: f64) -> f64 {
+        hubble_rad(temp, sm_geff(temp))
+    }
+    fn entropy_density(&self, temp: f64) -> f64 {
+        sm_entropy_density(temp)
+    }
+}
+
+impl Cosmology for EarlyMatterDomination {
+    fn geff(&self, temp: f64) -> f64 {
+        sm_geff(temp)
+    }
+    fn heff(&self, temp: f64) -> f64 {
+        sm_heff(temp)
+    }
+    fn dlnh_dlnt(&self, temp: f64) -> f64 {
+        StandardCosmology.dlnh_dlnt(temp)
+    }
+    fn hubble(&self, temp: f64) -> f64 {
+        let r = (temp / self.trh).powi(4);
+        hubble_rad(temp, sm_geff(temp)) * (1.0 + r).sqrt()
+    }
+    /// Interpolates between the entropy-conserving result and 8/3, the value
+    /// during matter domination with entropy injection.
+    fn dlna_dlnx(&self, temp: f64) -> f64 {
+        let r = (temp / self.trh).powi(4);
+        (1.0 + self.dlnh_dlnt(temp) / 3.0 + r * 8.0 / 3.0) / (1.0 + r)
+    }
+}
+
+impl Cosmology for Kination {
+    fn geff(&self, temp: f64) -> f64 {
+        sm_geff(temp)
+    }
+    fn heff(&self, temp: f64) -> f64 {
+        sm_heff(temp)
+    }
+    fn dlnh_dlnt(&self, temp: f64) -> f64 {
+        StandardCosmology.dlnh_dlnt(temp)
+    }
+    fn hubble(&self, temp: f64) -> f64 {
+        let r = (temp / self.tkin).powi(2);
+        hubble_rad(temp, sm_geff(temp)) * (1.0 + r).sqrt()
+    }
+}
+
+impl Cosmology for DarkRadiation {
+    fn geff(&self, temp: f64) -> f64 {
+        sm_geff(temp) + 7.0 / 4.0 * (4.0f64 / 11.0).powf(4.0 / 3.0) * self.delta_neff
+    }
+    /// The dark radiation is decoupled, so it doesn't contribute to the SM
+    /// entropy.
+    fn heff(&self, temp: f64) -> f64 {
+        sm_heff(temp)
+    }
+    fn dlnh_dlnt(&self, temp: f64) -> f64 {
+        StandardCosmology.dlnh_dlnt(temp)
+    }
+    fn hubble(&self, temp: f64) -> f64 {
+        hubble_rad(temp, self.geff(temp))
+    }
+    fn entropy_density(&self, temp: f64) -> f64 {
+        sm_entropy_density(temp)
+    }
+}
+
+impl DarkBath {
+    pub fn new(xi_ref: f64, tref: f64, gdark: f64) -> DarkBath {
+        DarkBath { xi_ref, tref, gdark }
+    }
+}
+
+impl Cosmology for DarkBath {
+    fn geff(&self, temp: f64) -> f64 {
+        sm_geff(temp) + self.gdark * self.xi(temp).powi(4)
+    }
+    /// Only the SM entropy is included, since the dark entropy is separately
+    /// conserved.
+    fn heff(&self, temp: f64) -> f64 {
+        sm_heff(temp)
+    }
+    fn dlnh_dlnt(&self, temp: f64) -> f64 {
+        StandardCosmology.dlnh_dlnt(temp)
+    }
+    fn hubble(&self, temp: f64) -> f64 {
+        hubble_rad(temp, self.geff(temp))
+    }
+    fn entropy_density(&self, temp: f64) -> f64 {
+        sm_entropy_density(temp)
+    }
+    fn xi(&self, temp: f64) -> f64 {
+        self.xi_ref * (sm_heff(temp) / sm_heff(self.tref)).powf(1.0 / 3.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64, rtol: f64) {
+        assert!((a - b).abs() <= rtol * b.abs(), "{} != {}", a, b);
+    }
+
+    /// Compare the expansion history of `cosmo` at `temp` with the standard
+    /// cosmology.
+    fn assert_standard<C: Cosmology>(cosmo: &C, temp: f64, rtol: f64) {
+        let std = StandardCosmology;
+        assert_close(cosmo.hubble(temp), std.hubble(temp), rtol);
+        assert_close(cosmo.hubblet(temp), std.hubblet(temp), rtol);
+        assert_close(cosmo.dlna_dlnx(temp), std.dlna_dlnx(temp), rtol);
+        assert_close(cosmo.entropy_density(temp), std.entropy_density(temp), rtol);
+        assert_close(cosmo.xi(temp), 1.0, rtol);
+    }
+
+    /// Once the extra component is negligible, each backend reduces to the
+    /// standard cosmology.
+    #[test]
+    fn test_standard_limits() {
+        for &temp in [1.0, 100.0].iter() {
+            assert_standard(&EarlyMatterDomination { trh: 1e3 * temp }, temp, 1e-10);
+            assert_standard(&Kination { tkin: 1e4 * temp }, temp, 1e-8);
+            assert_standard(&DarkRadiation { delta_neff: 0.0 }, temp, 1e-12);
+            let bath = DarkBath::new(0.0, 10.0, 2.0);
+            assert_close(bath.hubble(temp), StandardCosmology.hubble(temp), 1e-12);
+            assert_eq!(bath.xi(temp), 0.0);
+        }
+    }
+
+    /// Deep in the early matter and kination eras the Hubble rate is
+    /// enhanced by (T / T_RH)^2 and T / T_kin, and entropy injection during
+    /// matter domination gives a ~ T^(-8/3).
+    #[test]
+    fn test_non_standard_eras() {
+        let temp = 100.0;
+        let std = StandardCosmology;
+
+        let emd = EarlyMatterDomination { trh: 1e-3 * temp };
+        assert_close(emd.hubble(temp), std.hubble(temp) * 1e6, 1e-10);
+        assert_close(emd.dlna_dlnx(temp), 8.0 / 3.0, 1e-10);
+
+        let kin = Kination { tkin: 1e-4 * temp };
+        assert_close(kin.hubble(temp), std.hubble(temp) * 1e4, 1e-8);
+        assert_close(kin.dlna_dlnx(temp), std.dlna_dlnx(temp), 1e-12);
+
+        let dr = DarkRadiation { delta_neff: 1.0 };
+        assert_close(dr.geff(temp) - std.geff(temp), 0.454, 1e-3);
+    }
+
+    /// The dark bath has the reference temperature ratio at T_ref and
+    /// contributes g' ξ^4 to the energy density.
+    #[test]
+    fn test_dark_bath() {
+        let bath = DarkBath::new(0.5, 10.0, 2.0);
+        assert_close(bath.xi(bath.tref), bath.xi_ref, 1e-14);
+        for &temp in [1e-3, 1.0, 100.0].iter() {
+            let xi = bath.xi(temp);
+            assert_close(bath.geff(temp), sm_geff(temp) + 2.0 * xi.powi(4), 1e-12);
+            // The SM is heated as its d.o.f. decouple, so ξ can't exceed ξ_ref
+            // at lower temperatures.
+            if temp < bath.tref {
+                assert!(xi <= bath.xi_ref * (1.0 + 1e-12));
+            }
+            assert_close(
+                bath.hubble(temp),
+                hubble_rad(temp, sm_geff(temp) + 2.0 * xi.powi(4)),
+                1e-12,
+            );
+        }
+    }
+}
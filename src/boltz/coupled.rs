@@ -1,4 +1,45 @@
-pub trait CoupledBoltzmann {
+use super::full::momentum_grid;
+use super::traits::DarkMatterModel;
+
+pub trait CoupledBoltzmann: DarkMatterModel {
     /// Compute the lab velocity times cross section for chi+chibar -> anything
     fn sigma_vlab(&self, s: f64) -> f64;
+    /// Thermally averaged cross section at x = m / T_chi.
+    fn thermal_sigmav(&self, x: f64) -> f64;
+    /// Thermally averaged cross section weighted by p^2 / (3 E T_chi) at
+    /// x = m / T_chi, which enters the equation for the DM temperature.
+    fn thermal_sigmav2(&self, x: f64) -> f64;
+}
+
+impl<T: DarkMatterModel> CoupledBoltzmann for T {
+    fn sigma_vlab(&self, s: f64) -> f64 {
+        let m2 = self.mass().powi(2);
+        if s <= 4.0 * m2 {
+            return 0.0;
+        }
+        let vlab = (s * (s - 4.0 * m2)).sqrt() / (s - 2.0 * m2);
+        self.sigma(s.sqrt()) * vlab
+    }
+    fn thermal_sigmav(&self, x: f64) -> f64 {
+        self.thermal_average(x, |cme| self.sigma(cme))
+    }
+    fn thermal_sigmav2(&self, x: f64) -> f64 {
+        let qs = momentum_grid(100);
+        let n = qs.len();
+        // Maxwell-Boltzmann distribution, scaled by e^x to avoid underflow
+        let f = qs.mapv(|q| (x - (x * x + q * q).sqrt()).exp());
+        let mut num = 0.0;
+        let mut den = 0.0;
+        for i in 0..n {
+            let wi = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            let ei = (x * x + qs[i] * qs[i]).sqrt();
+            for k in 0..n {
+                let wk = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+                let wgt = wi * wk * (qs[i] * qs[k]).powi(2) * f[i] * f[k];
+                num += wgt * self.sigmav_angle_avg(x, qs[i], qs[k]) * qs[i] * qs[i] / (3.0 * ei);
+                den += wgt;
+            }
+        }
+        num / den
+    }
 }
@@ -0,0 +1,136 @@
+//! Decay source and sink terms. Heavier states ψ kept in equilibrium with
+//! the bath produce DM through two-body decays ψ -> chi + X, with X a bath
+//! particle, and the DM itself may decay into SM particles with a long
+//! lifetime. Inverse decays are included through detailed balance assuming
+//! Maxwell-Boltzmann statistics for ψ and X. For the number density,
+//!     dn/dt = Σ_ψ <Γ_ψ> n_ψ,eq (1 - n / n_eq) - <Γ_χ> (n - n_eq),
+//! where <Γ> = Γ K1(M/T) / K2(M/T) is the time-dilated thermal average of
+//! the rest-frame width Γ.
+
+use cyphus_specfun::bessel::CylBesselK;
+use haliax_constants::cosmology::AGE_OF_UNIVERSE;
+
+use super::cosmology::Cosmology;
+use super::helper::cosmic_time;
+
+/// Two-body decay ψ -> chi + X of a heavier state ψ in equilibrium with the
+/// bath into the DM and a bath particle X.
+#[derive(Clone, Copy, Debug)]
+pub struct TwoBodyDecay {
+    /// Mass of the decaying state.
+    pub mass: f64,
+    /// Internal d.o.f. of the decaying state.
+    pub g: f64,
+    /// Partial width for ψ -> chi + X in the rest frame of ψ.
+    pub width: f64,
+    /// Mass of the bath particle X.
+    pub m_other: f64,
+}
+
+/// Compute the time-dilated thermal average <Γ> = Γ K1(x) / K2(x) of the
+/// rest-frame width `width` of a particle at x = M / T.
+pub fn thermal_width(width: f64, x: f64) -> f64 {
+    width * x.cyl_bessel_k1_scaled() / x.cyl_bessel_kn_scaled(2)
+}
+
+/// Compute the comoving number density today of DM with lifetime 1 / `width`
+/// given its comoving number density `y` at the SM temperature `temp` where
+/// the Boltzmann equation was stopped. The solvers already deplete `y` by
+/// decays up to `temp`, so only the decays after t(`temp`) are applied.
+pub fn yield_today<C: Cosmology>(cosmo: &C, y: f64, width: f64, temp: f64) -> f64 {
+    y * (-width * (AGE_OF_UNIVERSE - cosmic_time(cosmo, temp))).exp()
+}
+
+impl TwoBodyDecay {
+    pub fn new(mass: f64, g: f64, width: f64, m_other: f64) -> TwoBodyDecay {
+        TwoBodyDecay {
+            mass,
+            g,
+            width,
+            m_other,
+        }
+    }
+    /// Compute the momentum of a DM particle with mass `mx` in the rest
+    /// frame of the decaying state.
+    pub fn daughter_momentum(&self, mx: f64) -> f64 {
+        let m2 = self.mass * self.mass;
+        let lam = (m2 - (mx + self.m_other).powi(2)) * (m2 - (mx - self.m_other).powi(2));
+        if lam > 0.0 {
+            lam.sqrt() / (2.0 * self.mass)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the thermally averaged width <Γ> at bath temperature `temp`.
+    pub fn thermal_width(&self, temp: f64) -> f64 {
+        thermal_width(self.width, self.mass / temp)
+    }
+    /// Compute the contribution of the decays to the phase-space
+    /// distribution of DM with mass `mx` and `g` d.o.f. at momentum `p`
+    /// and bath temperature `temp`. Returns (S, R) such that
+    ///     df/dt = S - R f,
+    /// where R = S e^{E/T} accounts for the inverse decays chi + X -> ψ.
+    pub fn phase_space_source(&self, mx: f64, g: f64, p: f64, temp: f64) -> (f64, f64) {
+        let ps = self.daughter_momentum(mx);
+        if ps == 0.0 {
+            return (0.0, 0.0);
+        }
+        let m = self.mass;
+        let e = (p * p + mx * mx).sqrt();
+        let es = (ps * ps + mx * mx).sqrt();
+        // Range of ψ energies which produce a DM particle with momentum p
+        let em = m * (e * es - p * ps) / (mx * mx);
+        let ep = m * (e * es + p * ps) / (mx * mx);
+
+        let pre = self.g * m * m * self.width * temp / (2.0 * g * e * p * ps)
+            * -(-(ep - em) / temp).exp_m1();
+        (pre * (-em / temp).exp(), pre * (-(em - e) / temp).exp())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use std::f64::consts::PI;
+
+    /// Integrating the source over the DM momenta must give the total decay
+    /// rate <Γ> n_ψ,eq.
+    #[test]
+    fn test_source_normalization() {
+        let (mx, g, temp) = (1.0, 2.0, 0.2);
+        let decay = TwoBodyDecay::new(1.3, 2.0, 1e-3, 0.1);
+        let n = 200_000;
+        let dp = 20.0 / n as f64;
+        let mut int = 0.0;
+        for i in 1..=n {
+            let p = i as f64 * dp;
+            let wgt = if i == n { 0.5 } else { 1.0 };
+            int += wgt * p * p * decay.phase_space_source(mx, g, p, temp).0;
+        }
+        let rate = g * int * dp / (2.0 * PI * PI);
+
+        let x = decay.mass / temp;
+        let expected = decay.width
+            * decay.g
+            * decay.mass.powi(2)
+            * temp
+            * x.cyl_bessel_k1_scaled()
+            * (-x).exp()
+            / (2.0 * PI * PI);
+        assert!((rate - expected).abs() < 1e-4 * expected);
+    }
+
+    /// Decays before the solver stopped must not be applied again. Deep in
+    /// radiation domination with constant d.o.f., t = 1 / (2H).
+    #[test]
+    fn test_yield_today() {
+        let cosmo = StandardCosmology;
+        let (y, temp) = (1e-10, 1e-5);
+        let t = 1.0 / (2.0 * cosmo.hubble(temp));
+        assert!((cosmic_time(&cosmo, temp) / t - 1.0).abs() < 1e-3);
+
+        let width = 1.0 / AGE_OF_UNIVERSE;
+        assert!(yield_today(&cosmo, y, width, temp) > y * (-1.0f64).exp());
+    }
+}
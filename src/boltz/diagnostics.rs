@@ -0,0 +1,217 @@
+//! Consistency diagnostics for solutions of the full Boltzmann equation. At
+//! each step of the solution we compute
+//!
+//! - the comoving number density Y and its change between steps compared
+//!   with the change predicted by integrating the annihilation and decay
+//!   rates (elastic scattering and expansion conserve Y),
+//! - the energy per unit entropy transferred from the DM to the bath by
+//!   annihilations, decays and elastic scattering,
+//! - the deviation of the DM temperature from that of `feq` while the DM is
+//!   tightly coupled to the bath (γ >> H).
+//!
+//! Large residuals point to an inconsistent collision term or momentum
+//! exchange rate, or to a momentum grid which is too coarse.
+
+use super::cosmology::Cosmology;
+use super::full::{
+    compute_annihilation_rates, compute_decay_terms, compute_elastic_dfi, temperature_ratio,
+    StepContext,
+};
+use super::traits::FullBoltzmann;
+use crate::utils::derivatives::*;
+use cyphus_diffeq::prelude::*;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+/// Diagnostics of a solution of the full Boltzmann equation, reported by
+/// `integrate_full_boltzmann` alongside the solution.
+pub struct FullBoltzmannReport {
+    /// Values of x = m / T at each step.
+    pub xs: Vec<f64>,
+    /// Comoving number density Y = n / s at each step.
+    pub yields: Vec<f64>,
+    /// Change in Y since the previous step minus the change obtained by
+    /// integrating the annihilation and decay rates. In chemical equilibrium
+    /// the net rate is a small difference of large rates, which no solver
+    /// resolves better than its tolerance times the rates. The residual is
+    /// therefore taken relative to Y plus the number of annihilations and
+    /// inverse annihilations (per unit entropy) during the step.
+    pub number_residual: Vec<f64>,
+    /// Cumulative energy per unit entropy transferred from the DM to the
+    /// bath since the first step.
+    pub energy_to_bath: Vec<f64>,
+    /// Ratio of the momentum exchange rate to the Hubble rate at each step.
+    pub gamma_over_hubble: Vec<f64>,
+    /// Relative deviation of the DM temperature from that of `feq`.
+    pub temperature_deviation: Vec<f64>,
+}
+
+/// Collision rates at a single step of the solution.
+struct StepRates {
+    /// Y = n / s
+    y: f64,
+    /// dY/dx from annihilations and decays
+    dy: f64,
+    /// dY/dx from annihilations and inverse annihilations separately
+    gross: f64,
+    /// d(ρ / s)/dx from annihilations, decays and elastic scattering
+    drho: f64,
+    /// γ / H
+    gam: f64,
+    /// |T_chi / T_chi,eq - 1|
+    dev: f64,
+}
+
+fn step_rates<T: FullBoltzmann, C: Cosmology>(
+    model: &T,
+    cosmo: &C,
+    x: f64,
+    f: ArrayView1<f64>,
+    qs: ArrayView1<f64>,
+) -> StepRates {
+    let n = qs.len();
+    let dq = qs[1] - qs[0];
+    let mx = model.mass();
+    let g = model.g();
+    let temp = mx / x;
+    let ctx = StepContext::new(model, cosmo, x, qs);
+    let feq = ctx.feq.view();
+    let df = first_deriv_vec(f, dq);
+    let d2f = second_deriv_vec(f, dq);
+    let (source, sink) = compute_decay_terms(x, ctx.ht, temp * ctx.xi, feq, qs, model);
+
+    let (mut y, mut dy, mut gross, mut drho) = (0.0, 0.0, 0.0, 0.0);
+    for i in 0..n {
+        let qi = qs[i];
+        let wgt = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+        let (gain, loss) = compute_annihilation_rates(i, f, f, &ctx, model);
+        let ann = gain - loss;
+        let el = if i != n - 1 {
+            compute_elastic_dfi(x, qi, f[i], df[i], d2f[i], ctx.gam, ctx.xi)
+        } else {
+            0.0
+        };
+        let dec = source[i] - sink[i] * f[i];
+        y += wgt * qi * qi * f[i];
+        dy += wgt * qi * qi * (ann + dec);
+        gross += wgt * qi * qi * (gain + loss);
+        drho += wgt * qi * qi * (x * x + qi * qi).sqrt() * (ann + dec + el);
+    }
+    let ypre = g * temp.powi(3) * dq / (2.0 * PI * PI * cosmo.entropy_density(temp));
+    let tau = temperature_ratio(x, f, qs);
+    let tau_eq = temperature_ratio(x, feq, qs);
+
+    StepRates {
+        y: ypre * y,
+        dy: ypre * dy,
+        gross: ypre * gross,
+        drho: ypre * temp * drho,
+        gam: ctx.gam,
+        dev: (tau / tau_eq - 1.0).abs(),
+    }
+}
+
+impl FullBoltzmannReport {
+    /// Compute the diagnostics at each step of the solution `sol` of the
+    /// full Boltzmann equation for `model` on the momentum grid `qs`.
+    pub(super) fn new<T: FullBoltzmann, C: Cosmology>(
+        model: &T,
+        cosmo: &C,
+        qs: ArrayView1<f64>,
+        sol: &OdeSolution,
+    ) -> FullBoltzmannReport {
+        assert!(
+            sol.us.iter().all(|f| f.len() == qs.len()),
+            "diagnostics require a single distribution on the momentum grid"
+        );
+        let rates: Vec<StepRates> = sol
+            .ts
+            .iter()
+            .zip(sol.us.iter())
+            .map(|(&x, f)| step_rates(model, cosmo, x, f.view(), qs))
+            .collect();
+
+        let mut number_residual = vec![0.0];
+        let mut energy_to_bath = vec![0.0];
+        for k in 1..rates.len() {
+            let (r0, r1) = (&rates[k - 1], &rates[k]);
+            let dx = sol.ts[k] - sol.ts[k - 1];
+            // Integrate the rates using the trapizoid rule
+            let predicted = 0.5 * dx * (r0.dy + r1.dy);
+            let scale = r1.y + 0.5 * dx * (r0.gross + r1.gross);
+            number_residual.push((r1.y - r0.y - predicted) / scale);
+            energy_to_bath.push(energy_to_bath[k - 1] - 0.5 * dx * (r0.drho + r1.drho));
+        }
+
+        FullBoltzmannReport {
+            xs: sol.ts.clone(),
+            yields: rates.iter().map(|r| r.y).collect(),
+            number_residual,
+            energy_to_bath,
+            gamma_over_hubble: rates.iter().map(|r| r.gam).collect(),
+            temperature_deviation: rates.iter().map(|r| r.dev).collect(),
+        }
+    }
+    /// Largest relative number density residual over all steps.
+    pub fn max_number_residual(&self) -> f64 {
+        self.number_residual
+            .iter()
+            .fold(0.0, |acc: f64, r| acc.max(r.abs()))
+    }
+    /// Largest deviation of the DM temperature from that of `feq` over the
+    /// steps where γ / H exceeds `min_gamma_over_hubble`.
+    pub fn max_tight_coupling_deviation(&self, min_gamma_over_hubble: f64) -> f64 {
+        self.gamma_over_hubble
+            .iter()
+            .zip(self.temperature_deviation.iter())
+            .filter(|(&gam, _)| gam > min_gamma_over_hubble)
+            .fold(0.0, |acc: f64, (_, &dev)| acc.max(dev))
+    }
+    /// Returns true if the number density residuals and the tight-coupling
+    /// deviations (for γ / H > 1e3) are all below `tol`.
+    pub fn is_consistent(&self, tol: f64) -> bool {
+        self.max_number_residual() < tol && self.max_tight_coupling_deviation(1e3) < tol
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::full::{integrate_full_boltzmann, momentum_grid};
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+
+    const N: usize = 50;
+    const XSPAN: (f64, f64) = (10.0, 50.0);
+
+    /// Diagnosing a solution with a collision term other than the one it was
+    /// computed with shows up in the number density residuals.
+    #[test]
+    fn test_broken_collision_term() {
+        let cosmo = StandardCosmology;
+        let qs = momentum_grid(N);
+        let model = ConstantCrossSection::new(1e-9);
+        let res = integrate_full_boltzmann(model, &cosmo, N, XSPAN);
+        assert!(res.report.max_number_residual() < 1e-3);
+
+        let broken = ConstantCrossSection::new(1e-8);
+        let report = FullBoltzmannReport::new(&broken, &cosmo, qs.view(), &res.sol);
+        assert!(report.max_number_residual() > 1e-2);
+    }
+
+    /// A solution which ignores the elastic scattering falls out of kinetic
+    /// equilibrium although γ >> H.
+    #[test]
+    fn test_broken_gamma() {
+        let cosmo = StandardCosmology;
+        let qs = momentum_grid(N);
+        let coupled = || ConstantCrossSection::new(1e-9).with_gamma(1e-12);
+        let res = integrate_full_boltzmann(coupled(), &cosmo, N, XSPAN);
+        assert!(res.report.is_consistent(1e-2));
+
+        let decoupled = ConstantCrossSection::new(1e-9);
+        let res = integrate_full_boltzmann(decoupled, &cosmo, N, XSPAN);
+        let report = FullBoltzmannReport::new(&coupled(), &cosmo, qs.view(), &res.sol);
+        assert!(report.max_tight_coupling_deviation(1e3) > 0.1);
+    }
+}
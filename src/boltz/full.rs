@@ -1,115 +1,347 @@
 use crate::utils::derivatives::*;
 use cyphus_diffeq::prelude::*;
-use haliax_constants::cosmology::M_PLANK;
-use haliax_thermal_functions::prelude::*;
 use ndarray::prelude::*;
 use ndarray::Zip;
-use std::f64::consts::PI;
 
+use super::cosmology::Cosmology;
+use super::diagnostics::FullBoltzmannReport;
+use super::helper::hubblet;
 use super::traits::FullBoltzmann;
 
-pub fn gefft(temp: f64) -> f64 {
-    sm_sqrt_gstar(temp) * sm_geff(temp).sqrt() / sm_heff(temp) - 1.0
+/// Quantities entering the collision terms which are shared by all momenta
+/// at a single step of the full Boltzmann solvers.
+pub struct StepContext<'a> {
+    /// x = m / T
+    pub x: f64,
+    /// Momentum grid q = p / T
+    pub qs: ArrayView1<'a, f64>,
+    /// Spacing of the momentum grid
+    pub dq: f64,
+    /// Hubble rate at T
+    pub ht: f64,
+    /// Prefactor of the annihilation integrals
+    pub pre: f64,
+    /// Ratio T' / T of the temperature of the bath the DM is in contact with
+    pub xi: f64,
+    /// Equilibrium distribution at T'
+    pub feq: Array1<f64>,
+    /// Momentum exchange rate over the Hubble rate
+    pub gam: f64,
+    /// Coefficient of the expansion term
+    pub gt: f64,
+    /// Fraction of bound states which decay before being dissociated
+    pub bsf: f64,
 }
 
-pub fn hubblet(temp: f64) -> f64 {
-    let h = (4.0 * PI.powi(3) * sm_geff(temp) / 45.0).sqrt() * temp * temp / M_PLANK;
-    h / (1.0 + gefft(temp))
+impl<'a> StepContext<'a> {
+    pub fn new<T: FullBoltzmann, C: Cosmology>(
+        p: &T,
+        cosmo: &C,
+        x: f64,
+        qs: ArrayView1<'a, f64>,
+    ) -> StepContext<'a> {
+        let mx = p.mass();
+        let temp = mx / x;
+        let ht = cosmo.hubblet(temp);
+        // The DM equilibrates with the bath at temperature T' = xi T
+        let xi = cosmo.xi(temp);
+        StepContext {
+            x,
+            qs,
+            dq: qs[1] - qs[0],
+            ht,
+            pre: mx.powi(3) * p.g() / (ht * x.powi(4) * 2.0 * std::f64::consts::PI.powi(2)),
+            xi,
+            feq: qs.mapv(|q| p.feq(x / xi, q / xi)),
+            // Models normalize gamma to the standard-cosmology Hubble rate
+            gam: p.gamma_hinv(x / xi) * hubblet(temp * xi) / ht,
+            gt: cosmo.gefft(temp),
+            bsf: p.bsf_decay_fraction(x / xi),
+        }
+    }
+    /// Trapizoid weight of `qs[k]` times qs[k]^2 times the annihilation
+    /// cross section of DM with momenta `qs[i]` and `qs[k]`.
+    fn sigmav<T: FullBoltzmann>(&self, p: &T, i: usize, k: usize) -> f64 {
+        let n = self.qs.len();
+        let (qi, qk) = (self.qs[i], self.qs[k]);
+        let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+        wgt * qk * qk * (p.sigmav(self.x, qi, qk) + self.bsf * p.sigmav_bsf(self.x, qi, qk))
+    }
+}
+
+/// Compute the rates of inverse annihilations into and annihilations out of
+/// `qs[i]` for DM with distribution `f` annihilating with partners with
+/// distribution `fbar`.
+pub fn compute_annihilation_rates<T: FullBoltzmann>(
+    i: usize,
+    f: ArrayView1<f64>,
+    fbar: ArrayView1<f64>,
+    ctx: &StepContext,
+    p: &T,
+) -> (f64, f64) {
+    let feq = &ctx.feq;
+    let (mut gain, mut loss) = (0.0, 0.0);
+    // Integrate the scattering matrix using trapizoid rule
+    for k in 0..ctx.qs.len() {
+        let sigmav = ctx.sigmav(p, i, k);
+        gain += sigmav * feq[i] * feq[k];
+        loss += sigmav * f[i] * fbar[k];
+    }
+    (gain * ctx.pre * ctx.dq, loss * ctx.pre * ctx.dq)
+}
+
+/// Compute the annihilation term of df/dx at `qs[i]` for DM with
+/// distribution `f` annihilating with partners with distribution `fbar`.
+/// For self-conjugate or symmetric DM, `fbar` is `f` itself.
+pub fn compute_annihilation_dfi<T: FullBoltzmann>(
+    i: usize,
+    f: ArrayView1<f64>,
+    fbar: ArrayView1<f64>,
+    ctx: &StepContext,
+    p: &T,
+) -> f64 {
+    let (gain, loss) = compute_annihilation_rates(i, f, fbar, ctx, p);
+    gain - loss
 }
 
+/// Compute df/dx at `qs[i]` from annihilations with partners with
+/// distribution `fbar`, elastic scattering and expansion, given the
+/// derivatives `dfi` and `d2fi` of `f` at `qs[i]`.
 pub fn compute_dfi<T: FullBoltzmann>(
     i: usize,
-    n: usize,
-    x: f64,
     f: ArrayView1<f64>,
-    feq: ArrayView1<f64>,
-    qs: ArrayView1<f64>,
-    dq: f64,
+    fbar: ArrayView1<f64>,
     dfi: f64,
     d2fi: f64,
-    pre: f64,
-    gam: f64,
-    gt: f64,
+    ctx: &StepContext,
     p: &T,
 ) -> f64 {
-    let qi = qs[i];
-    let fi = f[i];
-    let feqi = feq[i];
+    let qi = ctx.qs[i];
+    let mut deriv = compute_annihilation_dfi(i, f, fbar, ctx, p);
+    // We skip these terms at the end since df/dx(qf) = 0.0;
+    if i != ctx.qs.len() - 1 {
+        deriv += compute_elastic_dfi(ctx.x, qi, f[i], dfi, d2fi, ctx.gam, ctx.xi);
+        // Compute the expansion term
+        deriv += ctx.gt * qi / ctx.x * dfi;
+    }
+    deriv
+}
 
-    let mut deriv = 0.0;
-    // Integrate the scattering matrix using trapizoid rule
+/// Compute the elastic scattering term of df/dx at momentum `qi`. The terms
+/// proportional to the bath temperature pick up a factor of xi = T' / T.
+pub fn compute_elastic_dfi(
+    x: f64,
+    qi: f64,
+    fi: f64,
+    dfi: f64,
+    d2fi: f64,
+    gam: f64,
+    xi: f64,
+) -> f64 {
+    let xq = (x * x + qi * qi).sqrt();
+    gam / (2.0 * x) * (xi * xq * d2fi + (qi + xi * (2.0 * xq / qi + qi / xq)) * dfi + 3.0 * fi)
+}
+
+/// Compute the kinetic-equilibrium phase-space distribution with the same
+/// number density and second moment (i.e. the same DM temperature) as `f`.
+/// This is the distribution that DM self-scattering drives `f` towards.
+pub fn compute_fkin(x: f64, f: ArrayView1<f64>, qs: ArrayView1<f64>) -> Array1<f64> {
+    let n = qs.len();
+    let mut num = 0.0;
     for k in 0..n {
-        let qk = qs[k];
-        let feqk = feq[k];
-        let fk = f[k];
         let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
-        deriv += wgt * qk * qk * p.sigmav(x, qi, qk) * (feqi * feqk - fi * fk);
+        num += wgt * qs[k] * qs[k] * f[k];
     }
-    deriv *= pre * dq;
-    // We skip these terms at the end since df/dx(qf) = 0.0;
-    if i != n - 1 {
-        // Compute the elastic scattering term
-        let xq = (x * x + qi * qi).sqrt();
-        let dfi = dfi;
-        deriv += gam / (2.0 * x) * (xq * d2fi + (qi + 2.0 * xq / qi + qi / xq) * dfi + 3.0 * fi);
-        // Compute the expansion term
-        deriv += gt * qi / x * dfi;
+    // y = T_chi / T
+    let y = temperature_ratio(x, f, qs);
+    if num <= 0.0 || y.is_nan() || y <= 0.0 {
+        return Array1::<f64>::zeros(n);
     }
-    deriv
+    let mut fkin = qs.mapv(|q| (-((x * x + q * q).sqrt() - x) / y).exp());
+    let mut norm = 0.0;
+    for k in 0..n {
+        let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+        norm += wgt * qs[k] * qs[k] * fkin[k];
+    }
+    fkin *= num / norm;
+    fkin
 }
 
-pub fn compute_j_dfi<T: FullBoltzmann>(
+/// Compute the self-scattering term rate_i (fkin_i - f_i) given the
+/// self-scattering rates `rates`. Since the rates depend on momentum, the
+/// bare relaxation term changes the DM number and energy. We add a
+/// correction rate_i fkin_i (a + b E_i), which vanishes once f = fkin, with
+/// a and b chosen to make the number and energy moments of the term vanish.
+pub fn compute_self_scattering(
+    x: f64,
+    f: ArrayView1<f64>,
+    fkin: ArrayView1<f64>,
+    rates: ArrayView1<f64>,
+    qs: ArrayView1<f64>,
+) -> Array1<f64> {
+    let n = qs.len();
+    let es = qs.mapv(|q| (x * x + q * q).sqrt());
+    let mut term = Array1::from_shape_fn(n, |i| rates[i] * (fkin[i] - f[i]));
+    let phi = Array1::from_shape_fn(n, |i| rates[i] * fkin[i]);
+    // Moments int(q^2 E^k v) using the trapizoid rule
+    let moment = |v: &Array1<f64>, k: i32| -> f64 {
+        let mut sum = 0.0;
+        for i in 0..n {
+            let wgt = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            sum += wgt * qs[i] * qs[i] * es[i].powi(k) * v[i];
+        }
+        sum
+    };
+    let (num, energy) = (moment(&term, 0), moment(&term, 1));
+    let (m0, m1, m2) = (moment(&phi, 0), moment(&phi, 1), moment(&phi, 2));
+    let det = m0 * m2 - m1 * m1;
+    if det.is_nan() || det <= 0.0 {
+        return Array1::<f64>::zeros(n);
+    }
+    let a = (m1 * energy - m2 * num) / det;
+    let b = (m1 * num - m0 * energy) / det;
+    term += &(&phi * &es.mapv(|e| a + b * e));
+    term
+}
+
+/// Compute the self-scattering rate of a DM particle with momentum `qs[i]`
+/// off the DM distribution `f` (without the prefactor.)
+pub fn compute_self_rate<T: FullBoltzmann>(
     i: usize,
-    j: usize,
     n: usize,
     x: f64,
     f: ArrayView1<f64>,
     qs: ArrayView1<f64>,
     dq: f64,
+    p: &T,
+) -> f64 {
+    let qi = qs[i];
+    let mut rate = 0.0;
+    for k in 0..n {
+        let qk = qs[k];
+        let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+        rate += wgt * qk * qk * p.sigmav_self(x, qi, qk) * f[k];
+    }
+    rate * dq
+}
+
+/// Compute the derivative of `compute_dfi` at `qs[i]` with respect to f_j
+/// at fixed partner distribution `fbar`, given the derivatives `j_df` and
+/// `j_d2f` of df/dq and d^2f/dq^2 at `qs[i]` with respect to f_j.
+pub fn compute_j_dfi<T: FullBoltzmann>(
+    i: usize,
+    j: usize,
+    fbar: ArrayView1<f64>,
     j_df: f64,
     j_d2f: f64,
-    pre: f64,
-    gam: f64,
-    gt: f64,
+    ctx: &StepContext,
     p: &T,
 ) -> f64 {
+    let n = ctx.qs.len();
+    let qi = ctx.qs[i];
+    let kronecker = if i == j { 1.0 } else { 0.0 };
     let mut jac = 0.0;
-    let qi = qs[i];
-    // Integrate the jacobian of the scattering matrix using
-    // trapizoid rule
-    for k in 0..n {
-        jac += -qs[k].powi(2)
-            * p.sigmav(x, qi, qs[k])
-            * if k == 0 || k == n - 1 { 0.5 } else { 1.0 }
-            * pre
-            * dq
-            * if i == j && i == k {
-                f[k] + f[i]
-            } else if i == j {
-                f[k]
-            } else if i == k {
-                f[i]
-            } else {
-                0.0
-            }
+    if i == j {
+        // Annihilation rate off the partner distribution
+        for k in 0..n {
+            jac -= ctx.sigmav(p, i, k) * fbar[k];
+        }
+        jac *= ctx.pre * ctx.dq;
     }
     if i != n - 1 {
-        let xq = (x * x + qi * qi).sqrt();
-        jac += gam / (2.0 * x)
-            * (xq * j_d2f
-                + (qi + 2.0 * xq / qi + qi / xq) * j_df
-                + 3.0 * if i == j { 1.0 } else { 0.0 });
-        jac += gt * qi / x * j_df;
+        // The elastic term is linear in (f, df/dq, d^2f/dq^2)
+        jac += compute_elastic_dfi(ctx.x, qi, kronecker, j_df, j_d2f, ctx.gam, ctx.xi);
+        jac += ctx.gt * qi / ctx.x * j_df;
     }
     jac
 }
 
-pub fn integrate_full_boltzmann<T: FullBoltzmann + Sync>(
+/// Compute the derivative of `compute_dfi` at `qs[i]` with respect to the
+/// partner distribution fbar_j.
+pub fn compute_j_partner_dfi<T: FullBoltzmann>(
+    i: usize,
+    j: usize,
+    f: ArrayView1<f64>,
+    ctx: &StepContext,
+    p: &T,
+) -> f64 {
+    -ctx.pre * ctx.dq * ctx.sigmav(p, i, j) * f[i]
+}
+
+/// Compute the source S and sink R of the DM phase-space distribution from
+/// decays, such that df/dx = S - R f, at bath temperature `tb`.
+pub fn compute_decay_terms<T: FullBoltzmann>(
+    x: f64,
+    ht: f64,
+    tb: f64,
+    feq: ArrayView1<f64>,
+    qs: ArrayView1<f64>,
+    p: &T,
+) -> (Array1<f64>, Array1<f64>) {
+    let n = qs.len();
+    let mx = p.mass();
+    let temp = mx / x;
+    let width = p.decay_width();
+    let decays = p.decays();
+    let mut source = Array1::<f64>::zeros(n);
+    let mut sink = Array1::<f64>::zeros(n);
+    if width == 0.0 && decays.is_empty() {
+        return (source, sink);
+    }
+    for i in 0..n {
+        let k = qs[i] * temp;
+        // Time dilation of the DM decays
+        let rate = width * mx / (k * k + mx * mx).sqrt();
+        source[i] = rate * feq[i];
+        sink[i] = rate;
+        for d in decays.iter() {
+            let (si, ri) = d.phase_space_source(mx, p.g(), k, tb);
+            source[i] += si;
+            sink[i] += ri;
+        }
+    }
+    source /= ht * x;
+    sink /= ht * x;
+    (source, sink)
+}
+
+/// Construct the grid of momenta q = p / T used by `integrate_full_boltzmann`.
+pub fn momentum_grid(n: usize) -> Array1<f64> {
+    Array::linspace(1e-6, 50.0, n)
+}
+
+/// Compute the ratio of the DM temperature, defined through the second
+/// moment T_chi = <p^2 / E> / 3, to the bath temperature given the phase-space
+/// distribution `f` on the momentum grid `qs`.
+pub fn temperature_ratio(x: f64, f: ArrayView1<f64>, qs: ArrayView1<f64>) -> f64 {
+    let n = qs.len();
+    let mut num = 0.0;
+    let mut tmp = 0.0;
+    for k in 0..n {
+        let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+        let qk = qs[k];
+        num += wgt * qk * qk * f[k];
+        tmp += wgt * qk.powi(4) / (x * x + qk * qk).sqrt() * f[k];
+    }
+    tmp / (3.0 * num)
+}
+
+/// Result of integrating the full Boltzmann equation.
+pub struct FullBoltzmannSolution {
+    /// Solution of the underlying ODE: the phase-space distribution on
+    /// `momentum_grid(n)` vs. x.
+    pub sol: OdeSolution,
+    /// Consistency diagnostics at each step of the solution.
+    pub report: FullBoltzmannReport,
+}
+
+pub fn integrate_full_boltzmann<T: FullBoltzmann + Sync, C: Cosmology + Sync>(
     model: T,
+    cosmo: &C,
     n: usize,
     xspan: (f64, f64),
-) -> OdeSolution {
-    let qs: Array1<f64> = Array::linspace(1e-6, 50.0, n);
+) -> FullBoltzmannSolution {
+    let qs = momentum_grid(n);
     let dq = qs[1] - qs[0];
     // Weight vector for integration. We will use trapizoid rule.
     let mut wgts = Array1::<f64>::ones(n);
@@ -117,81 +349,138 @@ pub fn integrate_full_boltzmann<T: FullBoltzmann + Sync>(
     wgts[n - 1] /= 0.5;
 
     // Extract parameters that don't change
-    let mx = model.dm_mass();
-    let g = model.g();
+    let mx = model.mass();
+    let self_scatter = model.self_scattering();
     // Construct the jacobian of the df
     let j_df = jac_first_deriv_vec(n, dq);
     let j_d2f = jac_second_deriv_vec(n, dq);
 
     // Construct function for RHS of ODE.
-    let dudt = |deriv: ArrayViewMut1<f64>, f: ArrayView1<f64>, x: f64, p: &T| {
+    // The model is passed by reference so that it is still available to
+    // compute the diagnostics once the solution is known.
+    let dudt = |deriv: ArrayViewMut1<f64>, f: ArrayView1<f64>, x: f64, p: &&T| {
+        let p = *p;
+        let ctx = StepContext::new(p, cosmo, x, qs.view());
         let temp = mx / x;
-        let ht = hubblet(temp);
-        let pre = mx.powi(3) * g / (ht * x.powi(4) * 2.0 * std::f64::consts::PI.powi(2));
-        let feq = qs.mapv(|q| p.feq(x, q));
-        let gam = p.gamma_hinv(x);
-        let gt = gefft(temp);
         let df = first_deriv_vec(f.view(), dq);
         let d2f = second_deriv_vec(f.view(), dq);
+        let (source, sink) =
+            compute_decay_terms(x, ctx.ht, temp * ctx.xi, ctx.feq.view(), qs.view(), p);
+        // Self-scattering relaxes f towards a kinetic equilibrium
+        let (fkin, self_rates) = if self_scatter {
+            let fkin = compute_fkin(x, f.view(), qs.view());
+            let rates = Array1::from_shape_fn(n, |i| {
+                ctx.pre * compute_self_rate(i, n, x, f.view(), qs.view(), dq, p)
+            });
+            (fkin, rates)
+        } else {
+            (Array1::<f64>::zeros(n), Array1::<f64>::zeros(n))
+        };
+
+        let self_term = compute_self_scattering(x, f, fkin.view(), self_rates.view(), qs.view());
 
         // Construct the derivative in parallel
         Zip::indexed(deriv).par_apply(|i, d| {
-            *d = compute_dfi(
-                i,
-                n,
-                x,
-                f.view(),
-                feq.view(),
-                qs.view(),
-                dq,
-                df[i],
-                d2f[i],
-                pre,
-                gam,
-                gt,
-                p,
-            );
+            *d = self_term[i] + source[i] - sink[i] * f[i];
+            *d += compute_dfi(i, f, f, df[i], d2f[i], &ctx, p);
         });
     };
 
     // Construct function for the Jacobian of the RHS of ODE.
-    let dfdu = |jac: ArrayViewMut2<f64>, f: ArrayView1<f64>, x: f64, p: &T| {
+    let dfdu = |jac: ArrayViewMut2<f64>, f: ArrayView1<f64>, x: f64, p: &&T| {
+        let p = *p;
+        let ctx = StepContext::new(p, cosmo, x, qs.view());
         let temp = mx / x;
-        let ht = hubblet(temp);
-        let gt = gefft(temp);
-        let pre = mx.powi(3) * g / (ht * x.powi(4) * 2.0 * std::f64::consts::PI.powi(2));
-        let gam = p.gamma_hinv(x);
+        // Only keep the diagonal piece of the self-scattering term
+        let self_rates = if self_scatter {
+            Array1::from_shape_fn(n, |i| {
+                ctx.pre * compute_self_rate(i, n, x, f.view(), qs.view(), dq, p)
+            })
+        } else {
+            Array1::<f64>::zeros(n)
+        };
+
+        // Only the sink depends on f
+        let (_, sink) = compute_decay_terms(x, ctx.ht, temp * ctx.xi, ctx.feq.view(), qs.view(), p);
 
+        // The DM is its own annihilation partner
         Zip::indexed(jac).par_apply(|(i, j), d| {
-            *d = compute_j_dfi(
-                i,
-                j,
-                n,
-                x,
-                f.view(),
-                qs.view(),
-                dq,
-                j_df[[i, j]],
-                j_d2f[[i, j]],
-                pre,
-                gam,
-                gt,
-                p,
-            );
+            *d = if i == j {
+                -self_rates[i] - sink[i]
+            } else {
+                0.0
+            };
+            *d += compute_j_dfi(i, j, f, j_df[[i, j]], j_d2f[[i, j]], &ctx, p);
+            *d += compute_j_partner_dfi(i, j, f, &ctx, p);
         });
     };
 
     // Construct the initial condition (i.e. the initial phase space)
     let mut finit = Array1::<f64>::zeros(n);
+    let xi = cosmo.xi(mx / xspan.0);
     for (i, q) in qs.iter().enumerate() {
-        finit[i] = model.feq(xspan.0, *q);
+        finit[i] = model.feq(xspan.0 / xi, *q / xi);
     }
 
-    let mut integrator = OdeIntegratorBuilder::default(&dudt, finit, xspan, Radau5, model)
+    let mut integrator = OdeIntegratorBuilder::default(&dudt, finit, xspan, Radau5, &model)
         .abstol(1e-100)
         .reltol(1e-6)
         .dfdu(&dfdu)
         .build();
     integrator.integrate();
-    integrator.sol
+    let sol = integrator.sol;
+
+    let report = FullBoltzmannReport::new(&model, cosmo, qs.view(), &sol);
+    FullBoltzmannSolution { sol, report }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::asymmetric::yield_from_distribution;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+    use crate::boltz::traits::Statistics;
+
+    #[test]
+    fn test_self_scattering_moments() {
+        let x = 20.0;
+        let qs = momentum_grid(100);
+        let f = qs.mapv(|q| (-q * q / 30.0).exp() * (1.0 + 0.1 * q));
+        let fkin = compute_fkin(x, f.view(), qs.view());
+        let rates = qs.mapv(|q| 1.0 + q * q);
+        let term = compute_self_scattering(x, f.view(), fkin.view(), rates.view(), qs.view());
+
+        let n = qs.len();
+        let (mut num, mut energy, mut scale) = (0.0, 0.0, 0.0);
+        for i in 0..n {
+            let wgt = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+            let e = (x * x + qs[i] * qs[i]).sqrt();
+            num += wgt * qs[i] * qs[i] * term[i];
+            energy += wgt * qs[i] * qs[i] * e * term[i];
+            scale += wgt * qs[i] * qs[i] * e * term[i].abs();
+        }
+        assert!(num.abs() < 1e-12 * scale);
+        assert!(energy.abs() < 1e-12 * scale);
+    }
+
+    /// Without annihilations, self-scattering must leave Y unchanged.
+    #[test]
+    fn test_self_scattering_conserves_yield() {
+        let model = ConstantCrossSection::new(0.0)
+            .with_dof(1.0, Statistics::MaxwellBoltzmann)
+            .with_self_scattering(1e-3);
+        let cosmo = StandardCosmology;
+        let n = 100;
+        let qs = momentum_grid(n);
+        let sol = integrate_full_boltzmann(model, &cosmo, n, (10.0, 50.0)).sol;
+
+        let mx = 100.0;
+        let yield_at = |k: usize| {
+            let x = sol.ts[k];
+            yield_from_distribution(&cosmo, mx / x, 1.0, sol.us[k].view(), qs.view())
+        };
+        let (y0, y1) = (yield_at(0), yield_at(sol.ts.len() - 1));
+        assert!((y1 / y0 - 1.0).abs() < 1e-3);
+    }
 }
@@ -1,12 +1,38 @@
-use haliax_constants::cosmology::M_PLANK;
-use haliax_thermal_functions::prelude::*;
-use std::f64::consts::PI;
+use super::cosmology::{Cosmology, StandardCosmology};
+use cyphus_integration::prelude::*;
 
+/// Compute dln(a)/dln(x) - 1 assuming the standard cosmology.
 pub fn gefft(temp: f64) -> f64 {
-    sm_sqrt_gstar(temp) * sm_geff(temp).sqrt() / sm_heff(temp) - 1.0
+    StandardCosmology.gefft(temp)
 }
 
+/// Compute H / (1 + gefft) assuming the standard cosmology.
 pub fn hubblet(temp: f64) -> f64 {
-    let h = (4.0 * PI.powi(3) * sm_geff(temp) / 45.0).sqrt() * temp * temp / M_PLANK;
-    h / (1.0 + gefft(temp))
+    StandardCosmology.hubblet(temp)
+}
+
+/// Compute the temperature of the bath the DM is in contact with given the SM
+/// temperature.
+pub fn bath_temperature<C: Cosmology>(cosmo: &C, temp: f64) -> f64 {
+    cosmo.xi(temp) * temp
+}
+
+/// Compute x' = m / T' for the bath the DM is in contact with given
+/// x = m / T.
+pub fn bath_x<C: Cosmology>(cosmo: &C, mx: f64, x: f64) -> f64 {
+    x / cosmo.xi(mx / x)
+}
+
+/// Compute the cosmic time t = ∫ dln(T') / H_T(T') at the SM temperature
+/// `temp`, integrating from T' = `temp` up to temperatures where the
+/// integrand is negligible.
+pub fn cosmic_time<C: Cosmology>(cosmo: &C, temp: f64) -> f64 {
+    let gk = GaussKronrodIntegratorBuilder::default()
+        .epsrel(1e-8)
+        .epsabs(0.0)
+        .key(2)
+        .build();
+    // Integrate over y = ln(T' / T)
+    let f = |y: f64| 1.0 / cosmo.hubblet(temp * y.exp());
+    gk.integrate(f, 0.0, 40.0).val
 }
@@ -0,0 +1,155 @@
+//! Extraction of the kinetic decoupling temperature T_kd of the DM. We
+//! provide two definitions:
+//!
+//! - the temperature at which the momentum exchange rate equals the
+//!   expansion rate, γ(T_kd) = H(T_kd),
+//! - the temperature defined by the late-time DM temperature,
+//!   T_chi = T^2 / T_kd, where T_chi is obtained either from the full
+//!   solution or from the second-moment equation of the elastic scattering
+//!   term,
+//!   d(x τ)/dx = γ/H (ξ - τ) - 2 gefft τ,     τ = T_chi / T.
+
+use super::cosmology::Cosmology;
+use super::full::{momentum_grid, temperature_ratio};
+use super::helper::hubblet;
+use super::traits::FullBoltzmann;
+use cyphus_diffeq::prelude::*;
+use ndarray::prelude::*;
+
+/// Result of the kinetic decoupling computation.
+pub struct KineticDecoupling {
+    /// Value of x = m / T where γ = H. `None` if γ doesn't cross H in the
+    /// requested range.
+    pub x_gamma: Option<f64>,
+    /// Kinetic decoupling temperature defined by the late-time DM
+    /// temperature.
+    pub tkd: f64,
+    /// Solution to the second-moment equation for τ = T_chi / T.
+    pub sol: OdeSolution,
+}
+
+/// Find x = m / T in `xspan` at which the momentum exchange rate `γ` equals
+/// the Hubble rate.
+pub fn x_gamma_equals_hubble<T: FullBoltzmann, C: Cosmology>(
+    model: &T,
+    cosmo: &C,
+    xspan: (f64, f64),
+) -> Option<f64> {
+    let mx = model.mass();
+    // log(γ / H) as a function of log(x)
+    let g = |logx: f64| -> f64 {
+        let x = logx.exp();
+        let temp = mx / x;
+        let xi = cosmo.xi(temp);
+        (model.gamma_hinv(x / xi) * hubblet(temp * xi) / cosmo.hubblet(temp)).ln()
+    };
+    let mut lo = xspan.0.ln();
+    let mut hi = xspan.1.ln();
+    let glo = g(lo);
+    if glo * g(hi) > 0.0 {
+        return None;
+    }
+    for _ in 0..60 {
+        let mid = 0.5 * (lo + hi);
+        if g(mid) * glo > 0.0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some((0.5 * (lo + hi)).exp())
+}
+
+/// Compute T_kd from the ratio τ = T_chi / T at temperature `temp`, assuming
+/// the DM temperature redshifts as a^-2 afterwards.
+pub fn tkd_from_temperature_ratio<C: Cosmology>(cosmo: &C, temp: f64, tau: f64) -> f64 {
+    // T_chi = T^2 (h(T) / h(T_kd))^(2/3) / T_kd
+    let mut tkd = temp / tau;
+    for _ in 0..10 {
+        tkd = temp / tau * (cosmo.heff(temp) / cosmo.heff(tkd)).powf(2.0 / 3.0);
+    }
+    tkd
+}
+
+/// Compute the kinetic decoupling of the DM by solving the second-moment
+/// equation for the DM temperature driven by the model's `gamma_hinv`.
+pub fn kinetic_decoupling<T: FullBoltzmann, C: Cosmology>(
+    model: T,
+    cosmo: &C,
+    xspan: (f64, f64),
+) -> KineticDecoupling {
+    let mx = model.mass();
+    let x_gamma = x_gamma_equals_hubble(&model, cosmo, xspan);
+
+    let gam = |x: f64, p: &T| -> (f64, f64, f64) {
+        let temp = mx / x;
+        let xi = cosmo.xi(temp);
+        let g = p.gamma_hinv(x / xi) * hubblet(temp * xi) / cosmo.hubblet(temp);
+        (g, xi, cosmo.gefft(temp))
+    };
+    let dudt = |mut du: ArrayViewMut1<f64>, u: ArrayView1<f64>, x: f64, p: &T| {
+        let (g, xi, gt) = gam(x, p);
+        du[0] = (g * (xi - u[0]) - (1.0 + 2.0 * gt) * u[0]) / x;
+    };
+    let dfdu = |mut df: ArrayViewMut2<f64>, _u: ArrayView1<f64>, x: f64, p: &T| {
+        let (g, _, gt) = gam(x, p);
+        df[[0, 0]] = -(g + 1.0 + 2.0 * gt) / x;
+    };
+
+    let uinit = array![cosmo.xi(mx / xspan.0)];
+    let mut integrator = OdeIntegratorBuilder::default(&dudt, uinit, xspan, Radau5, model)
+        .dfdu(&dfdu)
+        .reltol(1e-8)
+        .abstol(1e-10)
+        .build();
+    integrator.integrate();
+    let sol = integrator.sol;
+
+    let tau = sol.us[sol.us.len() - 1][0];
+    let tkd = tkd_from_temperature_ratio(cosmo, mx / xspan.1, tau);
+
+    KineticDecoupling { x_gamma, tkd, sol }
+}
+
+/// Compute T_kd from the final phase-space distribution `f` at x = `xf`
+/// obtained from `integrate_full_boltzmann`.
+pub fn kinetic_decoupling_from_distribution<C: Cosmology>(
+    cosmo: &C,
+    mx: f64,
+    xf: f64,
+    f: ArrayView1<f64>,
+) -> f64 {
+    let qs = momentum_grid(f.len());
+    let tau = temperature_ratio(xf, f, qs.view());
+    tkd_from_temperature_ratio(cosmo, mx / xf, tau)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+
+    /// With γ ∝ T^6 and H ∝ T^2 at constant d.o.f., γ / H = c (x_ref / x)^4
+    /// crosses 1 at x = c^(1/4) x_ref.
+    #[test]
+    fn test_power_law_crossing() {
+        let (xref, c) = (0.1, 16.0);
+        let gamma = c * hubblet(100.0 / xref) * xref.powi(6);
+        let model = ConstantCrossSection::new(0.0).with_gamma_power_law(gamma, 6.0);
+        let x = x_gamma_equals_hubble(&model, &StandardCosmology, (0.01, 1.0)).unwrap();
+        assert!((x / (c.powf(0.25) * xref) - 1.0).abs() < 1e-6);
+        assert!(x_gamma_equals_hubble(&model, &StandardCosmology, (0.5, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_tkd_round_trip() {
+        let cosmo = StandardCosmology;
+        // T_kd away from the QCD transition, where the fixed point converges fast
+        for &(temp, tkd) in [(1e-5, 3e-3), (0.01, 50.0), (1.0, 500.0)].iter() {
+            let tau = temp / tkd * (cosmo.heff(temp) / cosmo.heff(tkd)).powf(2.0 / 3.0);
+            let t = tkd_from_temperature_ratio(&cosmo, temp, tau);
+            assert!((t / tkd - 1.0).abs() < 1e-8);
+        }
+    }
+}
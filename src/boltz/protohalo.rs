@@ -0,0 +1,154 @@
+//! Post-processing of the DM phase-space distribution into the small-scale
+//! cutoff of the matter power spectrum. After kinetic decoupling, the DM
+//! free-streams and acoustic oscillations in the bath damp perturbations
+//! below the horizon scale. These set the smallest protohalo mass:
+//!
+//! - free-streaming length (comoving), for a velocity which redshifts as 1/a,
+//!   λ_fs = v_kd / (a_kd H_kd) ln(a_eq / a_kd),
+//! - free-streaming mass
+//!   M_fs = (4π/3) ρ_M λ_fs^3,
+//! - acoustic oscillation mass, i.e. the DM mass inside the horizon at T_kd,
+//!   M_ao = (4π/3) ρ_M(T_kd) / H(T_kd)^3,
+//! - cutoff mass M_cut = max(M_fs, M_ao).
+
+use super::cosmology::Cosmology;
+use super::full::{momentum_grid, temperature_ratio};
+use super::kinetic_decoupling::tkd_from_temperature_ratio;
+use crate::constants::{GEV_PER_CM3_TO_GEV4, MPC_TO_INV_GEV, OMEGA_M_H2, SOLAR_MASS};
+use cyphus_diffeq::prelude::*;
+use haliax_constants::prelude::*;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+
+/// Temperature in GeV well after e+e- annihilation but before matter-radiation
+/// equality, used to normalize the radiation density today.
+const T_RADIATION: f64 = 1e-6;
+
+/// Small-scale cutoff of the matter power spectrum.
+#[derive(Clone, Copy, Debug)]
+pub struct Protohalo {
+    /// Kinetic decoupling temperature in GeV.
+    pub tkd: f64,
+    /// Comoving free-streaming length in Mpc.
+    pub lambda_fs: f64,
+    /// Free-streaming mass in solar masses.
+    pub m_fs: f64,
+    /// Acoustic-oscillation damping mass in solar masses.
+    pub m_ao: f64,
+    /// Smallest protohalo mass in solar masses.
+    pub m_cut: f64,
+}
+
+/// Matter density today in GeV^4.
+fn rho_matter_today() -> f64 {
+    OMEGA_M_H2 * RHO_CRIT * GEV_PER_CM3_TO_GEV4
+}
+
+/// Scale factor (a_today = 1) at temperature `temp`, using conservation of
+/// the comoving entropy.
+fn scale_factor<C: Cosmology>(cosmo: &C, temp: f64) -> f64 {
+    (S_TODAY * GEV_PER_CM3_TO_GEV4 / cosmo.entropy_density(temp)).cbrt()
+}
+
+/// Scale factor at matter-radiation equality, a_eq = ρ_R(today) / ρ_M(today).
+fn scale_factor_eq<C: Cosmology>(cosmo: &C) -> f64 {
+    let rho_r = PI * PI / 30.0 * cosmo.geff(T_RADIATION) * T_RADIATION.powi(4);
+    rho_r * scale_factor(cosmo, T_RADIATION).powi(4) / rho_matter_today()
+}
+
+impl Protohalo {
+    /// Compute the cutoff given the kinetic decoupling temperature `tkd` and
+    /// the rms DM velocity `vkd` at that temperature.
+    pub fn new<C: Cosmology>(cosmo: &C, tkd: f64, vkd: f64) -> Protohalo {
+        let rho_m0 = rho_matter_today();
+        let akd = scale_factor(cosmo, tkd);
+        let hkd = cosmo.hubble(tkd);
+
+        let lambda = vkd / (akd * hkd) * (scale_factor_eq(cosmo) / akd).ln().max(0.0);
+        let m_fs = 4.0 * PI / 3.0 * rho_m0 * lambda.powi(3) / SOLAR_MASS;
+
+        let rho_mkd = rho_m0 / akd.powi(3);
+        let m_ao = 4.0 * PI / 3.0 * rho_mkd / hkd.powi(3) / SOLAR_MASS;
+
+        Protohalo {
+            tkd,
+            lambda_fs: lambda / MPC_TO_INV_GEV,
+            m_fs,
+            m_ao,
+            m_cut: m_fs.max(m_ao),
+        }
+    }
+    /// Compute the cutoff from the kinetic decoupling temperature, assuming
+    /// a Maxwell-Boltzmann distribution with T_chi = T_kd at decoupling.
+    pub fn from_tkd<C: Cosmology>(cosmo: &C, mx: f64, tkd: f64) -> Protohalo {
+        Protohalo::new(cosmo, tkd, (3.0 * tkd / mx).sqrt())
+    }
+    /// Compute the cutoff from the phase-space distribution `f` at x = `xf`
+    /// obtained from `integrate_full_boltzmann`.
+    pub fn from_distribution<C: Cosmology>(
+        cosmo: &C,
+        mx: f64,
+        xf: f64,
+        f: ArrayView1<f64>,
+    ) -> Protohalo {
+        let qs = momentum_grid(f.len());
+        let tau = temperature_ratio(xf, f, qs.view());
+        let tkd = tkd_from_temperature_ratio(cosmo, mx / xf, tau);
+        // a v is conserved once the DM is non-relativistic, so we can
+        // translate the final rms velocity back to T_kd.
+        let n = qs.len();
+        let mut num = 0.0;
+        let mut q2 = 0.0;
+        for k in 0..n {
+            let wgt = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+            num += wgt * qs[k] * qs[k] * f[k];
+            q2 += wgt * qs[k].powi(4) * f[k];
+        }
+        let vf = (q2 / num).sqrt() / xf;
+        let vkd = vf * scale_factor(cosmo, mx / xf) / scale_factor(cosmo, tkd);
+        Protohalo::new(cosmo, tkd, vkd)
+    }
+    /// Compute the cutoff from the final point of the solution returned by
+    /// `integrate_full_boltzmann`.
+    pub fn from_solution<C: Cosmology>(cosmo: &C, mx: f64, sol: &OdeSolution) -> Protohalo {
+        let xf = sol.ts[sol.ts.len() - 1];
+        let f = &sol.us[sol.us.len() - 1];
+        Protohalo::from_distribution(cosmo, mx, xf, f.view())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+
+    /// Bringmann (2009): M_ao ≈ 3.4e-6 M_sun (T_kd g_eff^(1/4) / 50 MeV)^-3.
+    #[test]
+    fn test_reference_tkd() {
+        let cosmo = StandardCosmology;
+        let tkd = 0.03;
+        let halo = Protohalo::from_tkd(&cosmo, 100.0, tkd);
+        let m_ao = 3.4e-6 * (tkd * cosmo.geff(tkd).powf(0.25) / 0.05).powi(-3);
+        assert!(halo.m_ao / m_ao > 1.0 / 3.0 && halo.m_ao / m_ao < 3.0);
+        assert!(halo.m_cut >= halo.m_fs && halo.m_cut >= halo.m_ao);
+    }
+
+    /// Between muon and electron decoupling the d.o.f. are constant, so
+    /// M_ao ∝ T_kd^-3 and λ_fs ∝ T_kd^(-1/2) ln(a_eq / a_kd).
+    #[test]
+    fn test_tkd_scaling() {
+        let cosmo = StandardCosmology;
+        let (t1, t2) = (3e-3, 6e-3);
+        let (h1, h2) = (
+            Protohalo::from_tkd(&cosmo, 100.0, t1),
+            Protohalo::from_tkd(&cosmo, 100.0, t2),
+        );
+        assert!((h2.m_ao / h1.m_ao * 8.0 - 1.0).abs() < 1e-3);
+
+        let log = |t: f64| (scale_factor_eq(&cosmo) / scale_factor(&cosmo, t)).ln();
+        let expected = (t1 / t2).sqrt() * log(t2) / log(t1);
+        assert!((h2.lambda_fs / h1.lambda_fs / expected - 1.0).abs() < 1e-3);
+        let m_fs = (h2.lambda_fs / h1.lambda_fs).powi(3);
+        assert!((h2.m_fs / h1.m_fs / m_fs - 1.0).abs() < 1e-10);
+    }
+}
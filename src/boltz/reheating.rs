@@ -0,0 +1,285 @@
+//! Freeze-out during an early-matter-dominated era driven by a heavy modulus
+//! φ which decays into SM radiation. Since the decays inject entropy, the SM
+//! temperature no longer scales as 1/a and we can't use x = m / T as the
+//! time variable. Instead we evolve, as functions of A = ln(a / a_init),
+//!     u[0] = ln(ρ_φ a^3),
+//!     u[1] = ln(s a^3),
+//!     u[2] = ln(n_χ a^3),
+//! which obey
+//!     dρ_φ/dt = -3Hρ_φ - Γ_φ ρ_φ,
+//!     ds/dt = -3Hs + Γ_φ ρ_φ / T,
+//!     dn_χ/dt = -3Hn_χ - <σv>(n_χ^2 - n_eq^2) - <σv>_semi(n_χ^2 - n_χ n_eq)
+//!               - <σv^2>(n_χ^3 - n_χ^2 n_eq),
+//! where the last two terms come from semi-annihilations and 3 -> 2
+//! processes (see `DarkMatterModel`.) DM decays and production from decays
+//! of heavier states (`decay_width` and `decays`) are not included.
+
+use super::cosmology::Cosmology;
+use super::traits::SimpleBoltzmann;
+use cyphus_diffeq::prelude::*;
+use haliax_constants::cosmology::M_PLANK;
+use ndarray::prelude::*;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Parameters of the decaying modulus.
+#[derive(Clone, Copy, Debug)]
+pub struct Modulus {
+    /// Decay width of the modulus into SM radiation.
+    pub width: f64,
+    /// Initial fraction of the total energy density stored in the modulus.
+    pub energy_fraction: f64,
+}
+
+/// Reasons the modulus parameters are invalid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModulusError {
+    /// The decay width isn't positive.
+    NonPositiveWidth,
+    /// The energy fraction isn't in (0, 1).
+    EnergyFractionOutOfRange,
+}
+
+impl fmt::Display for ModulusError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            ModulusError::NonPositiveWidth => "modulus width must be positive",
+            ModulusError::EnergyFractionOutOfRange => "modulus energy fraction must be in (0, 1)",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for ModulusError {}
+
+/// Result of integrating the Boltzmann equation with a decaying modulus.
+pub struct ReheatingSolution {
+    /// Solution for (ln(ρ_φ a^3), ln(s a^3), ln(n_χ a^3)) vs. ln(a / a_init).
+    pub sol: OdeSolution,
+    /// Ratio of the comoving SM entropy at the end of the integration to
+    /// the initial comoving SM entropy.
+    pub dilution: f64,
+    /// Final DM comoving number density Y = n / s.
+    pub yinf: f64,
+}
+
+impl Modulus {
+    pub fn new(width: f64, energy_fraction: f64) -> Result<Modulus, ModulusError> {
+        let modulus = Modulus {
+            width,
+            energy_fraction,
+        };
+        modulus.validate()?;
+        Ok(modulus)
+    }
+    /// Check that the width is positive and 0 < `energy_fraction` < 1.
+    fn validate(&self) -> Result<(), ModulusError> {
+        if self.width.is_nan() || self.width <= 0.0 {
+            return Err(ModulusError::NonPositiveWidth);
+        }
+        if !(self.energy_fraction > 0.0 && self.energy_fraction < 1.0) {
+            return Err(ModulusError::EnergyFractionOutOfRange);
+        }
+        Ok(())
+    }
+    /// Estimate of the reheating temperature, defined by H(T_RH) = Γ_φ
+    /// assuming radiation domination with the d.o.f. of `cosmo`.
+    pub fn reheating_temperature<C: Cosmology>(&self, cosmo: &C) -> f64 {
+        let trh =
+            |geff: f64| (self.width * M_PLANK / (4.0 * PI.powi(3) * geff / 45.0).sqrt()).sqrt();
+        solve_for_temperature(|temp| trh(cosmo.geff(temp)))
+    }
+}
+
+/// Find the fixed point T = `f`(T), where `f` depends on T only through
+/// the slowly varying d.o.f.
+fn solve_for_temperature<F: Fn(f64) -> f64>(f: F) -> f64 {
+    // Start from the high-temperature d.o.f. of the SM
+    let mut temp = f(1e4);
+    for _ in 0..100 {
+        let next = f(temp);
+        if ((next - temp) / temp).abs() < 1e-12 {
+            return next;
+        }
+        temp = next;
+    }
+    temp
+}
+
+/// Compute the temperature of the radiation bath of `cosmo` from its energy
+/// density.
+pub fn temperature_from_rho<C: Cosmology>(cosmo: &C, rho: f64) -> f64 {
+    solve_for_temperature(|temp| (30.0 * rho / (PI * PI * cosmo.geff(temp))).powf(0.25))
+}
+
+/// Compute the temperature of the radiation bath of `cosmo` from its entropy
+/// density.
+pub fn temperature_from_entropy<C: Cosmology>(cosmo: &C, s: f64) -> f64 {
+    solve_for_temperature(|temp| temp * (s / cosmo.entropy_density(temp)).cbrt())
+}
+
+/// Compute the energy density of the radiation bath of `cosmo` from its
+/// temperature.
+pub fn rho_from_temperature<C: Cosmology>(cosmo: &C, temp: f64) -> f64 {
+    PI * PI / 30.0 * cosmo.geff(temp) * temp.powi(4)
+}
+
+/// Integrate the coupled modulus/radiation/DM system, starting from a
+/// radiation bath at T = m / `xmin`, until the SM temperature is guaranteed
+/// to have dropped below m / `xmax` (T decreases at least as fast as a^-3/8.)
+/// The d.o.f. of the radiation bath are taken from `cosmo`. Fails if the
+/// modulus parameters are invalid.
+pub fn integrate_reheating_boltzmann<T: SimpleBoltzmann, C: Cosmology>(
+    model: T,
+    cosmo: &C,
+    modulus: Modulus,
+    xmin: f64,
+    xmax: f64,
+) -> Result<ReheatingSolution, ModulusError> {
+    modulus.validate()?;
+    let mx = model.mass();
+    let width = modulus.width;
+
+    let hubble = |u: ArrayView1<f64>, a: f64, temp: f64| -> f64 {
+        let rho = (u[0] - 3.0 * a).exp() + rho_from_temperature(cosmo, temp);
+        (8.0 * PI * rho / 3.0).sqrt() / M_PLANK
+    };
+
+    let dudt = |mut du: ArrayViewMut1<f64>, u: ArrayView1<f64>, a: f64, p: &T| {
+        let temp = temperature_from_entropy(cosmo, (u[1] - 3.0 * a).exp());
+        let h = hubble(u, a, temp);
+        let x = mx / temp;
+        let n = (u[2] - 3.0 * a).exp();
+        let nn = p.equilibrium_density(temp);
+        let sigmav = p.thermal_cross_section(x);
+        let semi = p.semi_annihilation_sigmav(x);
+        let cann = p.cannibal_sigmav2(x);
+
+        du[0] = -width / h;
+        du[1] = width / h * (u[0] - u[1]).exp() / temp;
+        du[2] = -(sigmav * (n - nn * nn / n) + semi * (n - nn) + cann * n * (n - nn)) / h;
+    };
+    let dfdu = |mut df: ArrayViewMut2<f64>, u: ArrayView1<f64>, a: f64, p: &T| {
+        let temp = temperature_from_entropy(cosmo, (u[1] - 3.0 * a).exp());
+        let h = hubble(u, a, temp);
+        let x = mx / temp;
+        let n = (u[2] - 3.0 * a).exp();
+        let nn = p.equilibrium_density(temp);
+        let sigmav = p.thermal_cross_section(x);
+        let semi = p.semi_annihilation_sigmav(x);
+        let cann = p.cannibal_sigmav2(x);
+        let inj = width / h * (u[0] - u[1]).exp() / temp;
+
+        df.fill(0.0);
+        df[[1, 0]] = inj;
+        // T scales as s^(1/3) at fixed d.o.f.
+        df[[1, 1]] = -4.0 / 3.0 * inj;
+        df[[2, 2]] = -(sigmav * (n + nn * nn / n) + semi * n + cann * n * (2.0 * n - nn)) / h;
+    };
+
+    let temp = mx / xmin;
+    let rho_r = rho_from_temperature(cosmo, temp);
+    let rho_phi = modulus.energy_fraction / (1.0 - modulus.energy_fraction) * rho_r;
+    let s_init = cosmo.entropy_density(temp);
+    let nn = model.equilibrium_density(temp);
+    let uinit = array![
+        rho_phi.ln(),
+        s_init.ln(),
+        nn.ln()
+    ];
+    let aspan = (0.0, 8.0 / 3.0 * (xmax / xmin).ln());
+
+    let mut integrator = OdeIntegratorBuilder::default(&dudt, uinit, aspan, Radau5, model)
+        .dfdu(&dfdu)
+        .reltol(1e-7)
+        .abstol(1e-7)
+        .build();
+    integrator.integrate();
+    let sol = integrator.sol;
+
+    let uf = &sol.us[sol.us.len() - 1];
+    let dilution = uf[1].exp() / s_init;
+    let yinf = (uf[2] - uf[1]).exp();
+
+    Ok(ReheatingSolution {
+        sol,
+        dilution,
+        yinf,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::simple::integrate_simple_boltzmann;
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+    use crate::boltz::traits::DarkMatterModel;
+
+    #[test]
+    fn test_modulus_parameters() {
+        assert_eq!(
+            Modulus::new(1e-20, 1.0).unwrap_err(),
+            ModulusError::EnergyFractionOutOfRange
+        );
+        assert_eq!(
+            Modulus::new(0.0, 0.5).unwrap_err(),
+            ModulusError::NonPositiveWidth
+        );
+        assert!(Modulus::new(1e-20, 0.5).is_ok());
+    }
+
+    /// Hubble rate at the start of the integration.
+    fn initial_hubble(temp: f64, energy_fraction: f64) -> f64 {
+        let rho = rho_from_temperature(&StandardCosmology, temp) / (1.0 - energy_fraction);
+        (8.0 * PI * rho / 3.0).sqrt() / M_PLANK
+    }
+
+    /// A subdominant modulus decaying at H ~ Γ during radiation domination
+    /// increases the comoving radiation energy by r (π / (2Γ/H_init))^(1/2),
+    /// where r = ρ_φ / ρ_R initially.
+    #[test]
+    fn test_dilution() {
+        let model = ConstantCrossSection::new(1e-8);
+        let (xmin, xmax) = (1e-3, 1e-2);
+        let (r, ratio) = (1e-4, 1e-4);
+        let fraction = r / (1.0 + r);
+        let width = ratio * initial_hubble(model.mass() / xmin, fraction);
+        let modulus = Modulus::new(width, fraction).unwrap();
+        let res =
+            integrate_reheating_boltzmann(model, &StandardCosmology, modulus, xmin, xmax).unwrap();
+        let expected = (1.0 + r * (PI / (2.0 * ratio)).sqrt()).powf(0.75);
+        assert!(((res.dilution - 1.0) / (expected - 1.0) - 1.0).abs() < 3e-2);
+    }
+
+    /// A modulus which decays right away leaves the standard freeze-out
+    /// untouched.
+    #[test]
+    fn test_early_decay() {
+        let model = ConstantCrossSection::new(1e-8);
+        let cosmo = StandardCosmology;
+        let (xmin, xmax) = (1.0, 1000.0);
+        let width = 10.0 * initial_hubble(model.mass() / xmin, 1e-6);
+        let modulus = Modulus::new(width, 1e-6).unwrap();
+        let res =
+            integrate_reheating_boltzmann(model.clone(), &cosmo, modulus, xmin, xmax).unwrap();
+        assert!((res.dilution - 1.0).abs() < 1e-5);
+
+        // Compare with the standard result at the final temperature
+        let uf = &res.sol.us[res.sol.us.len() - 1];
+        let af = res.sol.ts[res.sol.ts.len() - 1];
+        let temp = temperature_from_entropy(&cosmo, (uf[1] - 3.0 * af).exp());
+        let sol = integrate_simple_boltzmann(model, &cosmo, xmin, 100.0 / temp);
+        let y = sol.us[sol.us.len() - 1][0].exp();
+        assert!((res.yinf / y - 1.0).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_temperature_from_rho() {
+        for &temp in [1e-3, 0.15, 1.0, 100.0].iter() {
+            let rho = rho_from_temperature(&StandardCosmology, temp);
+            let t = temperature_from_rho(&StandardCosmology, rho);
+            assert!((t / temp - 1.0).abs() < 1e-8);
+        }
+    }
+}
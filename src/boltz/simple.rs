@@ -1,11 +1,14 @@
+use super::cosmology::Cosmology;
+use super::decay::thermal_width;
+use super::helper::bath_temperature;
 use super::traits::SimpleBoltzmann;
 use cyphus_diffeq::prelude::*;
-use haliax_constants::prelude::*;
 use haliax_thermal_functions::prelude::*;
 use ndarray::prelude::*;
 
-pub fn integrate_simple_boltzmann<T: SimpleBoltzmann>(
+pub fn integrate_simple_boltzmann<T: SimpleBoltzmann, C: Cosmology>(
     model: T,
+    cosmo: &C,
     xmin: f64,
     xmax: f64,
 ) -> OdeSolution {
@@ -13,35 +16,71 @@ pub fn integrate_simple_boltzmann<T: SimpleBoltzmann>(
     let dudt = |mut dw: ArrayViewMut1<f64>, w: ArrayView1<f64>, logx: f64, p: &T| {
         let x: f64 = logx.exp();
         let temp: f64 = mx / x;
-        let s: f64 = sm_entropy_density(temp);
-        let n = neq(temp, mx, 2.0, 1);
+        // Temperature of the bath the DM is in contact with
+        let tb: f64 = bath_temperature(cosmo, temp);
+        let s: f64 = cosmo.entropy_density(temp);
+        let n = p.equilibrium_density(tb);
         let weq: f64 = (n / s).ln();
         let ww: f64 = w[0];
 
-        let pf: f64 = -(std::f64::consts::PI / 45.0).sqrt() * M_PLANK * sm_sqrt_gstar(temp) * temp;
-        let sigmav: f64 = p.thermal_cross_section(x);
+        let pf: f64 = -s / cosmo.hubblet(temp);
+        let sigmav: f64 = p.thermal_cross_section(mx / tb);
+        let semi: f64 = p.semi_annihilation_sigmav(mx / tb);
+        let cann: f64 = s * p.cannibal_sigmav2(mx / tb);
+        // Dilution of Y from entropy injection (vanishes if entropy is conserved)
+        let dil: f64 = 3.0 + cosmo.dlnh_dlnt(temp) - 3.0 * cosmo.dlna_dlnx(temp);
+        // Decays of the DM and production from decays of heavier states
+        let dec: f64 = thermal_width(p.decay_width(), mx / tb) * (1.0 - (weq - ww).exp());
+        let prod: f64 = p
+            .decays()
+            .iter()
+            .map(|d| {
+                let wd = (neq(tb, d.mass, d.g, 1) / s).ln();
+                d.thermal_width(tb) * ((wd - ww).exp() - (wd - weq).exp())
+            })
+            .sum();
 
-        // dW_e / dlogx
-        dw[0] = pf * sigmav * (ww.exp() - (2.0 * weq - ww).exp());
+        // dW_e / dlogx: chi+chi <-> SM, chi+chi <-> chi+φ, 3 <-> 2 and decays
+        dw[0] = pf
+            * (sigmav * (ww.exp() - (2.0 * weq - ww).exp())
+                + semi * (ww.exp() - weq.exp())
+                + cann * ((2.0 * ww).exp() - (ww + weq).exp()))
+            + (prod - dec) / cosmo.hubblet(temp)
+            + dil;
     };
     let dfdu = |mut df: ArrayViewMut2<f64>, w: ArrayView1<f64>, logx: f64, p: &T| {
         let x: f64 = logx.exp();
         let temp: f64 = mx / x;
-        let s: f64 = sm_entropy_density(temp);
+        let tb: f64 = bath_temperature(cosmo, temp);
+        let s: f64 = cosmo.entropy_density(temp);
 
-        let n = neq(temp, mx, 2.0, 1);
+        let n = p.equilibrium_density(tb);
         let weq: f64 = (n / s).ln();
         let ww: f64 = w[0];
 
-        let pf: f64 = -(std::f64::consts::PI / 45.0).sqrt() * M_PLANK * sm_sqrt_gstar(temp) * temp;
-        let sigmav: f64 = p.thermal_cross_section(x);
+        let pf: f64 = -s / cosmo.hubblet(temp);
+        let sigmav: f64 = p.thermal_cross_section(mx / tb);
+        let semi: f64 = p.semi_annihilation_sigmav(mx / tb);
+        let cann: f64 = s * p.cannibal_sigmav2(mx / tb);
+        let dec: f64 = thermal_width(p.decay_width(), mx / tb) * (weq - ww).exp();
+        let prod: f64 = p
+            .decays()
+            .iter()
+            .map(|d| d.thermal_width(tb) * neq(tb, d.mass, d.g, 1) / s * (-ww).exp())
+            .sum();
 
         // dW_e / dlogx
-        df[[0, 0]] = pf * sigmav * (ww.exp() + (2.0 * weq - ww).exp());
+        df[[0, 0]] = pf
+            * (sigmav * (ww.exp() + (2.0 * weq - ww).exp())
+                + semi * ww.exp()
+                + cann * (2.0 * (2.0 * ww).exp() - (ww + weq).exp()))
+            - (prod + dec) / cosmo.hubblet(temp);
     };
     let temp = mx / xmin;
-    let n = neq(temp, mx, 2.0, 1);
-    let uinit = array![(n / sm_entropy_density(temp)).ln()];
+    let tb = bath_temperature(cosmo, temp);
+    // Total equilibrium density of the DM and its coannihilation partners
+    let n = model.equilibrium_density(tb);
+    let uinit = array![(n / cosmo.entropy_density(temp)).ln()];
     let tspan = (xmin.ln(), xmax.ln());
 
     let mut integrator = OdeIntegratorBuilder::default(&dudt, uinit, tspan, Radau5, model)
@@ -52,3 +91,64 @@ pub fn integrate_simple_boltzmann<T: SimpleBoltzmann>(
     integrator.integrate();
     integrator.sol
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::traits::fixture::ConstantCrossSection;
+    use crate::boltz::traits::{DarkMatterModel, Statistics};
+
+    /// The initial yield is the equilibrium yield with the model's own d.o.f.
+    /// and statistics.
+    #[test]
+    fn test_initial_yield() {
+        let cosmo = StandardCosmology;
+        let model = ConstantCrossSection::new(1e-8).with_dof(1.0, Statistics::Boson);
+        let sol = integrate_simple_boltzmann(model, &cosmo, 1.0, 10.0);
+        let yeq = neq(100.0, 100.0, 1.0, -1) / cosmo.entropy_density(100.0);
+        assert!((sol.us[0][0].exp() / yeq - 1.0).abs() < 1e-12);
+    }
+
+    /// With only semi-annihilation (Γ = n_eq <σv>) or only cannibalization
+    /// (Γ = n_eq^2 <σv^2>) the DM tracks equilibrium while Γ > H and
+    /// freezes out once Γ drops below H.
+    #[test]
+    fn test_number_changing_freeze_out() {
+        let cosmo = StandardCosmology;
+        let models = [
+            ConstantCrossSection::new(0.0).with_semi_annihilation(1e-9),
+            ConstantCrossSection::new(0.0).with_cannibalization(1e-5),
+        ];
+        for model in models.iter() {
+            let rate = |x: f64| {
+                let temp = 100.0 / x;
+                let n = model.equilibrium_density(temp);
+                let sigmav = model.semi_annihilation_sigmav(x) + model.cannibal_sigmav2(x) * n;
+                n * sigmav / cosmo.hubblet(temp)
+            };
+            // Γ / H falls monotonically, so bisect for Γ = H
+            let (mut lo, mut hi) = (1.0_f64, 1e3_f64);
+            for _ in 0..100 {
+                let mid = (lo * hi).sqrt();
+                if rate(mid) > 1.0 {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let xf = lo;
+            assert!(xf > 5.0 && xf < 100.0, "x_f = {}", xf);
+
+            let sol = integrate_simple_boltzmann(model.clone(), &cosmo, 1.0, 1e3);
+            let ratio = |x: f64| {
+                let k = sol.ts.iter().position(|&t| t >= x.ln()).unwrap();
+                let temp = 100.0 / sol.ts[k].exp();
+                let yeq = model.equilibrium_density(temp) / cosmo.entropy_density(temp);
+                sol.us[k][0].exp() / yeq
+            };
+            assert!((ratio(xf / 2.0) - 1.0).abs() < 1e-2, "x_f = {}", xf);
+            assert!(ratio(2.0 * xf) > 1e3, "x_f = {}", xf);
+        }
+    }
+}
@@ -1,4 +1,349 @@
-pub trait FullBoltzmann {
+use super::decay::TwoBodyDecay;
+use super::helper::hubblet;
+use crate::bound_state::BoundState;
+use crate::models::SmFermion;
+use crate::sommerfeld::Sommerfeld;
+use crate::utils::integration::*;
+use cyphus_integration::prelude::*;
+use cyphus_specfun::bessel::CylBesselK;
+use haliax_thermal_functions::prelude::*;
+use std::num::FpCategory;
+
+/// Quantum statistics of the dark matter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Statistics {
+    /// Fermi-Dirac statistics.
+    Fermion,
+    /// Bose-Einstein statistics.
+    Boson,
+    /// Maxwell-Boltzmann statistics.
+    MaxwellBoltzmann,
+}
+
+impl Statistics {
+    /// Occupation number given the energy over temperature `e`.
+    pub fn occupation(&self, e: f64) -> f64 {
+        match self {
+            Statistics::Fermion => 1.0 / (e.exp() + 1.0),
+            Statistics::Boson => 1.0 / (e.exp() - 1.0),
+            Statistics::MaxwellBoltzmann => (-e).exp(),
+        }
+    }
+    /// Sign η in the occupation number 1 / (e^{E/T} + η), as expected by
+    /// `neq`.
+    pub fn eta(&self) -> i32 {
+        match self {
+            Statistics::Fermion => 1,
+            Statistics::Boson => -1,
+            Statistics::MaxwellBoltzmann => 0,
+        }
+    }
+}
+
+/// Core trait for dark matter models. Any type implementing it automatically
+/// implements `FullBoltzmann`, `SimpleBoltzmann` and `CoupledBoltzmann` and
+/// can therefore be used with all the solvers.
+pub trait DarkMatterModel {
+    /// Dark matter mass
+    fn mass(&self) -> f64;
+    /// Dark matter d.o.f.
+    fn g(&self) -> f64;
+    /// Quantum statistics of the dark matter.
+    fn statistics(&self) -> Statistics;
+    /// Annihilation cross section for chi+chi -> anything given the
+    /// center-of-mass energy.
+    fn sigma(&self, cme: f64) -> f64;
+    /// Momentum exchange rate between the DM and the SM at x = m / T,
+    /// obtained by integrating the elastic scattering amplitude chi+f -> chi+f
+    /// over the bath.
+    fn momentum_exchange_rate(&self, x: f64) -> f64;
+    /// Sommerfeld enhancement from a light mediator, if any.
+    fn sommerfeld(&self) -> Option<&Sommerfeld> {
+        None
+    }
+    /// Bound state formed by exchanging a light mediator, if any.
+    fn bound_state(&self) -> Option<BoundState> {
+        None
+    }
+    /// Self-scattering cross section chi+chi -> chi+chi given the
+    /// center-of-mass energy, or `None` if self-scattering is neglected.
+    fn sigma_self(&self, _cme: f64) -> Option<f64> {
+        None
+    }
+    /// Locations of the resonances and thresholds of `sigma` in units of
+    /// z = cme / m. Only points with z > 2 are used.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![]
+    }
+    /// Masses and internal d.o.f. of the coannihilation partners of the DM.
+    fn partners(&self) -> Vec<(f64, f64)> {
+        vec![]
+    }
+    /// Annihilation cross section for species i + species j -> anything given
+    /// the center-of-mass energy. Species 0 is the DM and species k > 0 is
+    /// `partners()[k - 1]`.
+    fn sigma_coann(&self, i: usize, j: usize, cme: f64) -> f64 {
+        if i == 0 && j == 0 {
+            self.sigma(cme)
+        } else {
+            0.0
+        }
+    }
+    /// Thermally averaged cross section <σv> for the semi-annihilation
+    /// chi+chi -> chi+φ at x = m / T, with φ in equilibrium with the bath. It
+    /// enters the number density as dn/dt = -<σv> (n^2 - n n_eq), so any
+    /// symmetry factors (e.g. 1/2 for Z3 DM) must be included. Models can
+    /// build it from a cross section with `thermal_average`.
+    fn semi_annihilation_sigmav(&self, _x: f64) -> f64 {
+        0.0
+    }
+    /// Thermally averaged rate <σv^2> for the 3 -> 2 process
+    /// chi+chi+chi -> chi+chi at x = m / T. It enters the number density as
+    /// dn/dt = -<σv^2> (n^3 - n^2 n_eq), with any symmetry factors included.
+    fn cannibal_sigmav2(&self, _x: f64) -> f64 {
+        0.0
+    }
+    /// Rest-frame width of the DM for decays into SM particles. It enters the
+    /// number density as dn/dt = -<Γ> (n - n_eq) with <Γ> = Γ K1 / K2. Only
+    /// `integrate_simple_boltzmann` and `integrate_full_boltzmann` include
+    /// the decays; the asymmetric and reheating solvers ignore them.
+    fn decay_width(&self) -> f64 {
+        0.0
+    }
+    /// Two-body decays ψ -> chi + X of heavier states ψ in equilibrium with
+    /// the bath, which produce DM (see `boltz::decay`.) The decaying states
+    /// must not also be listed in `partners`. As for `decay_width`, these
+    /// are ignored by the asymmetric and reheating solvers.
+    fn decays(&self) -> Vec<TwoBodyDecay> {
+        vec![]
+    }
+    /// Compute the Sommerfeld factor given the center-of-mass energy.
+    fn sommerfeld_factor(&self, cme: f64) -> f64 {
+        match self.sommerfeld() {
+            Some(sf) => sf.factor_cme(self.mass(), cme),
+            None => 1.0,
+        }
+    }
+    /// Compute sigma*vmol averaged over angles of two incoming DM particles
+    /// with momenta q = k1 / T and qt = k2 / T.
+    fn sigmav_angle_avg(&self, x: f64, q: f64, qt: f64) -> f64 {
+        angle_average(self.mass(), x, q, qt, |cme| {
+            self.sigma(cme) * self.sommerfeld_factor(cme)
+        })
+    }
+    /// Compute the thermal average of `sigma` (as a function of the
+    /// center-of-mass energy) times the Moller velocity at x = m / T.
+    fn thermal_average<F>(&self, x: f64, sigma: F) -> f64
+    where
+        F: Fn(f64) -> f64,
+        Self: Sized,
+    {
+        let m = self.mass();
+        let denom = 2.0 * x.cyl_bessel_kn_scaled(2);
+        let pf = x / (denom * denom);
+        let singular_points = self
+            .singular_points()
+            .into_iter()
+            .filter(|z| *z > 2.0)
+            .collect();
+        let gk_tcs = GaussKronrodIntegratorBuilder::default()
+            .singular_points(singular_points)
+            .epsrel(1e-8)
+            .epsabs(0.0)
+            .key(2)
+            .build();
+
+        let integrand = |z: f64| -> f64 {
+            let z2 = z * z;
+            let sig = sigma(m * z) * self.sommerfeld_factor(m * z);
+            let kernal = z2 * (z2 - 4.0) * (x * z).cyl_bessel_k1_scaled() * (-x * (z - 2.0)).exp();
+            sig * kernal
+        };
+
+        pf * gk_tcs.integrate(integrand, 2.0, f64::INFINITY).val
+    }
+    /// Compute the ratio of the total equilibrium number density of the DM
+    /// and its coannihilation partners to that of the DM alone at x = m / T.
+    fn neq_ratio(&self, x: f64) -> f64 {
+        let m = self.mass();
+        let partners: f64 = self
+            .partners()
+            .iter()
+            .map(|&(mi, gi)| {
+                let r = mi / m;
+                gi * r * r * (x * r).cyl_bessel_kn_scaled(2) * (-x * (r - 1.0)).exp()
+            })
+            .sum();
+        1.0 + partners / (self.g() * x.cyl_bessel_kn_scaled(2))
+    }
+    /// Compute the total equilibrium number density of the DM and its
+    /// coannihilation partners at the temperature `temp`.
+    fn equilibrium_density(&self, temp: f64) -> f64 {
+        let m = self.mass();
+        neq(temp, m, self.g(), self.statistics().eta()) * self.neq_ratio(m / temp)
+    }
+    /// Compute the effective thermally averaged cross section including
+    /// coannihilations at x = m / T (Edsjö & Gondolo):
+    ///     <σv>_eff = Σ_ij g_i g_j ∫ds √s K1(√s/T) p_ij^2 σ_ij
+    ///                / (2T (Σ_i g_i m_i^2 K2(m_i/T))^2).
+    /// The Sommerfeld factor of the DM multiplies σ_00. Any enhancement of
+    /// the other pairs must be included in `sigma_coann`.
+    fn thermal_average_coann(&self, x: f64) -> f64
+    where
+        Self: Sized,
+    {
+        let m = self.mass();
+        let mut species = vec![(m, self.g())];
+        species.extend(self.partners());
+        let n = species.len();
+
+        let den: f64 = species
+            .iter()
+            .map(|&(mi, gi)| {
+                let r = mi / m;
+                gi * mi * mi * (x * r).cyl_bessel_kn_scaled(2) * (-x * (r - 1.0)).exp()
+            })
+            .sum();
+
+        // Thresholds of each pair along with the resonances of `sigma`
+        let mut singular_points: Vec<f64> = self.singular_points();
+        for &(mi, _) in species.iter() {
+            for &(mj, _) in species.iter() {
+                singular_points.push((mi + mj) / m);
+            }
+        }
+        singular_points.retain(|z| *z > 2.0);
+        singular_points.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        singular_points.dedup();
+        let gk_tcs = GaussKronrodIntegratorBuilder::default()
+            .singular_points(singular_points)
+            .epsrel(1e-8)
+            .epsabs(0.0)
+            .key(2)
+            .build();
+
+        let integrand = |z: f64| -> f64 {
+            let cme = m * z;
+            let s = cme * cme;
+            let mut sum = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    let (mi, gi) = species[i];
+                    let (mj, gj) = species[j];
+                    if s > (mi + mj).powi(2) {
+                        let p2 = (s - (mi + mj).powi(2)) * (s - (mi - mj).powi(2)) / (4.0 * s);
+                        let sig = if i == 0 && j == 0 {
+                            self.sigma_coann(i, j, cme) * self.sommerfeld_factor(cme)
+                        } else {
+                            self.sigma_coann(i, j, cme)
+                        };
+                        sum += gi * gj * p2 * sig;
+                    }
+                }
+            }
+            z * z * (x * z).cyl_bessel_k1_scaled() * (-x * (z - 2.0)).exp() * sum
+        };
+
+        x * m * m * gk_tcs.integrate(integrand, 2.0, f64::INFINITY).val / (den * den)
+    }
+}
+
+/// Average `sigma` (as a function of the center-of-mass energy) times the
+/// Moller velocity over the angle between two DM particles of mass `m` with
+/// momenta q = k1 / T and qt = k2 / T.
+pub fn angle_average<F>(m: f64, x: f64, q: f64, qt: f64, sigma: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    let temp = m / x;
+    let k1 = q * temp;
+    let k2 = qt * temp;
+    let m2 = m * m;
+    let e1 = (k1 * k1 + m2).sqrt();
+    let e2 = (k2 * k2 + m2).sqrt();
+
+    let mut sum = 0.0;
+    for (theta, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+        // p1.p2
+        let dot = e1 * e2 - k1 * k2 * theta;
+        // Moler velocity
+        let vmol = (dot * dot - m.powi(4)).sqrt() / (e1 * e2);
+        // s = (p1 + p2)^2
+        let cme = (2.0 * m2 + 2.0 * dot).sqrt();
+        let t = wgt * vmol * sigma(cme);
+        sum += if t.classify() == FpCategory::Nan {
+            0.0
+        } else {
+            t
+        };
+    }
+    sum / 2.0
+}
+
+/// Compute f(1 ∓ f) k^4 <|M|^2>_t for a bath particle with statistics `stat`,
+/// mass `mf` and energy `w` at temperature `temp`, where
+///     <|M|^2>_t = 1/(8k^4) ∫_{-4k^2}^0 dt (-t)|M|^2
+/// and `msqrd(t)` is the squared matrix element for elastic scattering off
+/// the DM, summed over the spins of all particles.
+pub fn elastic_gamma_integrand<F>(w: f64, temp: f64, mf: f64, stat: Statistics, msqrd: F) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    if w <= mf {
+        return 0.0;
+    }
+    let e = w / temp;
+    let temp_fac = match stat {
+        Statistics::Fermion => 1.0 / (2.0 * (1.0 + e.cosh())),
+        Statistics::Boson => 1.0 / (2.0 * (e.cosh() - 1.0)),
+        Statistics::MaxwellBoltzmann => (-e).exp(),
+    };
+    let k2 = w * w - mf * mf;
+
+    // Map z in [-1, 1] to t in [-4k^2, 0]
+    let mut int = 0.0;
+    for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+        let t = -2.0 * k2 * (1.0 - z);
+        int += wgt * (-t) * msqrd(t);
+    }
+    int *= 2.0 * k2 / 8.0;
+
+    temp_fac * int
+}
+
+/// Compute the momentum exchange rate of DM with mass `m` and `g` d.o.f. at
+/// x = m / T from elastic scattering off the SM fermions `fermions` and
+/// their anti-particles:
+///     γ = 1 / (48 π^3 g m^3 T) Σ_f 2 ∫ dw f(1 - f) k^4 <|M|^2>_t,
+/// where `msqrd(f, w, t)` is the spin-summed squared matrix element for
+/// scattering off `f` with energy `w` in the DM rest frame.
+pub fn fermion_momentum_exchange_rate<I, F>(m: f64, g: f64, x: f64, fermions: I, msqrd: F) -> f64
+where
+    I: IntoIterator<Item = SmFermion>,
+    F: Fn(&SmFermion, f64, f64) -> f64,
+{
+    let gk_gamma = GaussKronrodIntegratorBuilder::default()
+        .epsrel(1e-8)
+        .epsabs(0.0)
+        .key(2)
+        .build();
+    let temp = m / x;
+    let pre = 1.0 / (48.0 * std::f64::consts::PI.powi(3) * g * m.powi(3) * temp);
+    let int: f64 = fermions
+        .into_iter()
+        .map(|f| {
+            let integrand = |w: f64| {
+                2.0 * elastic_gamma_integrand(w, temp, f.mass, Statistics::Fermion, |t| {
+                    msqrd(&f, w, t)
+                })
+            };
+            gk_gamma.integrate(integrand, f.mass, f64::INFINITY).val
+        })
+        .sum();
+    pre * int
+}
+
+pub trait FullBoltzmann: DarkMatterModel {
     /// Equillibrium phase-space distribution evaluated at momentum `q` and
     /// x = m / T.
     fn feq(&self, x: f64, q: f64) -> f64;
@@ -6,20 +351,211 @@ pub trait FullBoltzmann {
     fn gamma_hinv(&self, x: f64) -> f64;
     /// velocity-weighted  cross  section  averaged  over angles
     fn sigmav(&self, x: f64, q: f64, qt: f64) -> f64;
-    /// Dark matter mass
-    fn dm_mass(&self) -> f64;
-    /// Dark matter d.o.f.
-    fn g(&self) -> f64;
+    /// Velocity-weighted self-scattering cross section chi+chi -> chi+chi
+    /// averaged over angles. Only used if `self_scattering` returns true.
+    fn sigmav_self(&self, x: f64, q: f64, qt: f64) -> f64;
+    /// Returns true if the DM self-scattering term should be included in the
+    /// collision integral.
+    fn self_scattering(&self) -> bool;
+    /// Velocity-weighted bound-state formation cross section averaged over
+    /// angles.
+    fn sigmav_bsf(&self, x: f64, q: f64, qt: f64) -> f64;
+    /// Fraction of formed bound states which decay before being ionized.
+    fn bsf_decay_fraction(&self, x: f64) -> f64;
 }
 
-pub trait SimpleBoltzmann {
+pub trait SimpleBoltzmann: DarkMatterModel {
     fn thermal_cross_section(&self, x: f64) -> f64;
-    fn mass(&self) -> f64;
 }
 
-pub trait CoupledBoltzmann {
-    fn sigmav(&self, x: f64) -> f64;
-    fn sigmav2(&self, x: f64) -> f64;
-    fn sigmav_neq(&self, x: f64) -> f64;
-    fn sigmav2_neq(&self, x: f64) -> f64;
+impl<T: DarkMatterModel> FullBoltzmann for T {
+    fn feq(&self, x: f64, q: f64) -> f64 {
+        let e = (q * q + x * x).sqrt(); // energy / temperature
+        self.statistics().occupation(e)
+    }
+    fn gamma_hinv(&self, x: f64) -> f64 {
+        self.momentum_exchange_rate(x) / hubblet(self.mass() / x)
+    }
+    fn sigmav(&self, x: f64, q: f64, qt: f64) -> f64 {
+        self.sigmav_angle_avg(x, q, qt)
+    }
+    fn sigmav_self(&self, x: f64, q: f64, qt: f64) -> f64 {
+        angle_average(self.mass(), x, q, qt, |cme| {
+            self.sigma_self(cme).unwrap_or(0.0)
+        })
+    }
+    fn self_scattering(&self) -> bool {
+        self.sigma_self(2.0 * self.mass()).is_some()
+    }
+    fn sigmav_bsf(&self, x: f64, q: f64, qt: f64) -> f64 {
+        match self.bound_state() {
+            Some(bs) => bs.sigmav_angle_avg(self.mass(), x, q, qt),
+            None => 0.0,
+        }
+    }
+    fn bsf_decay_fraction(&self, x: f64) -> f64 {
+        match self.bound_state() {
+            Some(bs) => bs.decay_fraction(self.mass(), x),
+            None => 1.0,
+        }
+    }
+}
+
+impl<T: DarkMatterModel> SimpleBoltzmann for T {
+    fn thermal_cross_section(&self, x: f64) -> f64 {
+        let bsf = match self.bound_state() {
+            Some(bs) => bs.effective_thermal_sigmav(self.mass(), x),
+            None => 0.0,
+        };
+        if self.partners().is_empty() {
+            self.thermal_average(x, |cme| self.sigma(cme)) + bsf
+        } else {
+            self.thermal_average_coann(x) + bsf
+        }
+    }
+}
+
+/// Configurable model shared by the solver tests.
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::*;
+
+    /// DM with m = 100 GeV, g = 2, Maxwell-Boltzmann statistics, a constant
+    /// annihilation cross section and a momentum exchange rate γ ∝ x^-n,
+    /// constant by default. The other processes are off unless switched on.
+    #[derive(Clone)]
+    pub(crate) struct ConstantCrossSection {
+        g: f64,
+        statistics: Statistics,
+        sigma: f64,
+        gamma: f64,
+        gamma_power: f64,
+        sigma_self: Option<f64>,
+        partners: Vec<(f64, f64)>,
+        sigma_partners: f64,
+        sommerfeld: Option<Sommerfeld>,
+        semi: f64,
+        cannibal: f64,
+    }
+
+    impl ConstantCrossSection {
+        pub(crate) fn new(sigma: f64) -> ConstantCrossSection {
+            ConstantCrossSection {
+                g: 2.0,
+                statistics: Statistics::MaxwellBoltzmann,
+                sigma,
+                gamma: 0.0,
+                gamma_power: 0.0,
+                sigma_self: None,
+                partners: vec![],
+                sigma_partners: 0.0,
+                sommerfeld: None,
+                semi: 0.0,
+                cannibal: 0.0,
+            }
+        }
+        pub(crate) fn with_dof(mut self, g: f64, statistics: Statistics) -> Self {
+            self.g = g;
+            self.statistics = statistics;
+            self
+        }
+        pub(crate) fn with_gamma(mut self, gamma: f64) -> Self {
+            self.gamma = gamma;
+            self
+        }
+        /// Use the momentum exchange rate γ = `gamma` x^-`power`.
+        pub(crate) fn with_gamma_power_law(mut self, gamma: f64, power: f64) -> Self {
+            self.gamma = gamma;
+            self.gamma_power = power;
+            self
+        }
+        pub(crate) fn with_self_scattering(mut self, sigma: f64) -> Self {
+            self.sigma_self = Some(sigma);
+            self
+        }
+        /// Add a partner of mass `mass` and `g` d.o.f. All pairs other than
+        /// the DM pair annihilate with the cross section `sigma`.
+        pub(crate) fn with_partner(mut self, mass: f64, g: f64, sigma: f64) -> Self {
+            self.partners.push((mass, g));
+            self.sigma_partners = sigma;
+            self
+        }
+        pub(crate) fn with_sommerfeld(mut self, sommerfeld: Sommerfeld) -> Self {
+            self.sommerfeld = Some(sommerfeld);
+            self
+        }
+        pub(crate) fn with_semi_annihilation(mut self, sigmav: f64) -> Self {
+            self.semi = sigmav;
+            self
+        }
+        pub(crate) fn with_cannibalization(mut self, sigmav2: f64) -> Self {
+            self.cannibal = sigmav2;
+            self
+        }
+    }
+
+    impl DarkMatterModel for ConstantCrossSection {
+        fn mass(&self) -> f64 {
+            100.0
+        }
+        fn g(&self) -> f64 {
+            self.g
+        }
+        fn statistics(&self) -> Statistics {
+            self.statistics
+        }
+        fn sigma(&self, _cme: f64) -> f64 {
+            self.sigma
+        }
+        fn momentum_exchange_rate(&self, x: f64) -> f64 {
+            self.gamma * x.powf(-self.gamma_power)
+        }
+        fn sommerfeld(&self) -> Option<&Sommerfeld> {
+            self.sommerfeld.as_ref()
+        }
+        fn sigma_self(&self, _cme: f64) -> Option<f64> {
+            self.sigma_self
+        }
+        fn partners(&self) -> Vec<(f64, f64)> {
+            self.partners.clone()
+        }
+        fn sigma_coann(&self, i: usize, j: usize, cme: f64) -> f64 {
+            match (i, j) {
+                (0, 0) => self.sigma(cme),
+                _ => self.sigma_partners,
+            }
+        }
+        fn semi_annihilation_sigmav(&self, _x: f64) -> f64 {
+            self.semi
+        }
+        fn cannibal_sigmav2(&self, _x: f64) -> f64 {
+            self.cannibal
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::fixture::ConstantCrossSection;
+    use super::*;
+    use crate::sommerfeld::SommerfeldApprox;
+
+    /// Partners which are much heavier than the DM are Boltzmann suppressed,
+    /// so `thermal_average_coann` must reduce to `thermal_average`, including
+    /// the Sommerfeld factor.
+    #[test]
+    fn test_heavy_partners() {
+        let model = ConstantCrossSection::new(1e-9)
+            .with_partner(1e3, 4.0, 1e-6)
+            .with_sommerfeld(Sommerfeld::new(0.3, 0.0, SommerfeldApprox::Coulomb));
+        for &x in [20.0, 50.0, 100.0].iter() {
+            let coann = model.thermal_average_coann(x);
+            let expected = model.thermal_average(x, |cme| model.sigma(cme));
+            assert!((coann - expected).abs() < 1e-6 * expected);
+            // The enhancement must not be dropped
+            let bare =
+                model.thermal_average(x, |cme| model.sigma(cme) / model.sommerfeld_factor(cme));
+            assert!(coann > 1.2 * bare);
+        }
+    }
 }
@@ -0,0 +1,152 @@
+//! Bound-state formation (BSF) of a pair of DM particles via the emission of a
+//! light mediator, followed by the decay of the bound state. We only include
+//! the ground state of a Coulomb-like potential with coupling `alpha_b`. The
+//! formation cross section is
+//!     σ_BSF v = (2^9 / 3) π α_rad α_B / m^2 S_BSF(ζ),
+//!     S_BSF(ζ) = 2πζ / (1 - exp(-2πζ)) ζ^4 / (1 + ζ^2)^2 exp(-4ζ acot(ζ)),
+//! with ζ = α_B / v_rel. Bound states which are ionized by the bath before they
+//! decay don't deplete the DM, so the effective cross section entering the
+//! Boltzmann equation is
+//!     <σv>_eff = <σ_BSF v> Γ_dec / (Γ_dec + Γ_ion).
+
+use crate::utils::integration::*;
+use cyphus_integration::prelude::*;
+use std::f64::consts::PI;
+
+/// Parameters of the bound state.
+#[derive(Clone, Copy, Debug)]
+pub struct BoundState {
+    /// Coupling of the potential binding the DM.
+    pub alpha_b: f64,
+    /// Coupling of the emitted mediator.
+    pub alpha_rad: f64,
+    /// Number of internal d.o.f. of the DM.
+    pub gdm: f64,
+    /// Number of internal d.o.f. of the bound state.
+    pub gbound: f64,
+    /// Decay width of the bound state. If `None`, the para-positronium-like
+    /// width m α_B^5 / 2 is used.
+    pub width: Option<f64>,
+}
+
+impl BoundState {
+    pub fn new(alpha_b: f64, alpha_rad: f64, gdm: f64, gbound: f64) -> BoundState {
+        BoundState {
+            alpha_b,
+            alpha_rad,
+            gdm,
+            gbound,
+            width: None,
+        }
+    }
+    /// Set the decay width of the bound state.
+    pub fn with_width(mut self, width: f64) -> BoundState {
+        self.width = Some(width);
+        self
+    }
+    /// Binding energy of the ground state for DM of mass `mx`.
+    pub fn binding_energy(&self, mx: f64) -> f64 {
+        mx * self.alpha_b.powi(2) / 4.0
+    }
+    /// Decay width of the ground state for DM of mass `mx`.
+    pub fn decay_width(&self, mx: f64) -> f64 {
+        match self.width {
+            Some(w) => w,
+            None => mx * self.alpha_b.powi(5) / 2.0,
+        }
+    }
+    /// Compute σ_BSF v for DM of mass `mx` and relative velocity `vrel`.
+    pub fn sigmav(&self, mx: f64, vrel: f64) -> f64 {
+        if vrel <= 0.0 {
+            return 0.0;
+        }
+        let zeta = self.alpha_b / vrel;
+        let y = 2.0 * PI * zeta;
+        let sbsf = y / (1.0 - (-y).exp()) * zeta.powi(4) / (1.0 + zeta * zeta).powi(2)
+            * (-4.0 * zeta * (1.0 / zeta).atan()).exp();
+        512.0 / 3.0 * PI * self.alpha_rad * self.alpha_b / (mx * mx) * sbsf
+    }
+    /// Compute σ_BSF v given the center-of-mass energy.
+    pub fn sigmav_cme(&self, mx: f64, cme: f64) -> f64 {
+        let v2 = 1.0 - 4.0 * mx * mx / (cme * cme);
+        if v2 > 0.0 {
+            self.sigmav(mx, 2.0 * v2.sqrt())
+        } else {
+            0.0
+        }
+    }
+    /// Compute σ_BSF v averaged over the angle between two incoming DM
+    /// particles with momenta q = k / T and qt = kt / T where T = mx / x.
+    pub fn sigmav_angle_avg(&self, mx: f64, x: f64, q: f64, qt: f64) -> f64 {
+        let temp = mx / x;
+        let k1 = q * temp;
+        let k2 = qt * temp;
+        let e1 = (k1 * k1 + mx * mx).sqrt();
+        let e2 = (k2 * k2 + mx * mx).sqrt();
+
+        let mut sum = 0.0;
+        for (theta, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+            let dot = e1 * e2 - k1 * k2 * theta;
+            let cme = (2.0 * mx * mx + 2.0 * dot).sqrt();
+            sum += wgt * self.sigmav_cme(mx, cme);
+        }
+        sum / 2.0
+    }
+    /// Compute the thermal average of σ_BSF v using a Maxwell-Boltzmann
+    /// distribution for the relative velocity at x = mx / T.
+    pub fn thermal_sigmav(&self, mx: f64, x: f64) -> f64 {
+        let gk = GaussKronrodIntegratorBuilder::default()
+            .epsrel(1e-8)
+            .epsabs(0.0)
+            .key(2)
+            .build();
+        // v_rel = 2 w / sqrt(x)
+        let f = |w: f64| -> f64 { w * w * (-w * w).exp() * self.sigmav(mx, 2.0 * w / x.sqrt()) };
+        4.0 / PI.sqrt() * gk.integrate(f, 0.0, f64::INFINITY).val
+    }
+    /// Compute the ionization rate of the bound state at x = mx / T from
+    /// the thermally averaged formation cross section using detailed balance.
+    pub fn ionization_rate(&self, mx: f64, x: f64, sigmav: f64) -> f64 {
+        let temp = mx / x;
+        let mu = mx / 2.0;
+        sigmav * self.gdm.powi(2) / self.gbound
+            * (mu * temp / (2.0 * PI)).powf(1.5)
+            * (-self.binding_energy(mx) / temp).exp()
+    }
+    /// Fraction of bound states which decay rather than being ionized.
+    pub fn decay_fraction(&self, mx: f64, x: f64) -> f64 {
+        let sigmav = self.thermal_sigmav(mx, x);
+        let gdec = self.decay_width(mx);
+        gdec / (gdec + self.ionization_rate(mx, x, sigmav))
+    }
+    /// Compute the effective thermally averaged BSF cross section at x.
+    pub fn effective_thermal_sigmav(&self, mx: f64, x: f64) -> f64 {
+        let sigmav = self.thermal_sigmav(mx, x);
+        let gdec = self.decay_width(mx);
+        sigmav * gdec / (gdec + self.ionization_rate(mx, x, sigmav))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Bound states which decay right away all deplete the DM, while those
+    /// which are ionized long before they decay contribute in proportion to
+    /// Γ_dec / Γ_ion.
+    #[test]
+    fn test_effective_sigmav_limits() {
+        let (mx, x) = (1e3, 20.0);
+        let bs = BoundState::new(0.1, 0.1, 2.0, 1.0);
+        let sigmav = bs.thermal_sigmav(mx, x);
+        let gion = bs.ionization_rate(mx, x, sigmav);
+        assert!(sigmav > 0.0 && gion > 0.0);
+
+        let fast = bs.with_width(1e10 * gion);
+        assert!((fast.effective_thermal_sigmav(mx, x) / sigmav - 1.0).abs() < 1e-9);
+
+        let slow = bs.with_width(1e-10 * gion);
+        let expected = sigmav * 1e-10;
+        assert!((slow.effective_thermal_sigmav(mx, x) / expected - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,36 @@
+//! Unit conversions and cosmological parameters shared by the relic density,
+//! direct-detection and indirect-detection code which are not provided by
+//! `haliax_constants`. All conversions are derived from a few base values so
+//! that they stay consistent with each other.
+
+/// Observed DM relic density Ω h^2 (Planck 2018).
+pub const OMEGA_OBS_H2: f64 = 0.120;
+/// Baryon density Ω_b h^2 (Planck 2018).
+pub const OMEGA_B_H2: f64 = 0.0224;
+/// Total matter density Ω_M h^2.
+pub const OMEGA_M_H2: f64 = OMEGA_OBS_H2 + OMEGA_B_H2;
+
+/// Speed of light in km/s.
+pub const SPEED_OF_LIGHT: f64 = 299_792.458;
+/// Conversion from cm to GeV^-1 (1 / ħc).
+pub const CM_TO_INV_GEV: f64 = 5.067_731e13;
+/// Conversion from fm to GeV^-1.
+pub const FM_TO_INV_GEV: f64 = CM_TO_INV_GEV * 1e-13;
+/// Conversion from Mpc to GeV^-1.
+pub const MPC_TO_INV_GEV: f64 = 3.085_678e24 * CM_TO_INV_GEV;
+/// Conversion factor from GeV^-2 to cm^2.
+pub const GEV2_TO_CM2: f64 = 1.0 / (CM_TO_INV_GEV * CM_TO_INV_GEV);
+/// Conversion factor from GeV^-2 to cm^3/s.
+pub const GEV2_TO_CM3_PER_S: f64 = GEV2_TO_CM2 * SPEED_OF_LIGHT * 1e5;
+/// Conversion from GeV / cm^3 to GeV^4.
+pub const GEV_PER_CM3_TO_GEV4: f64 = 1.0 / (CM_TO_INV_GEV * CM_TO_INV_GEV * CM_TO_INV_GEV);
+/// Conversion from GeV to s^-1 (1 / ħ).
+pub const GEV_TO_INV_S: f64 = 1.519_267e24;
+/// One kilogram in GeV.
+pub const KG_TO_GEV: f64 = 5.609_589e26;
+/// Solar mass in GeV.
+pub const SOLAR_MASS: f64 = 1.988_47e30 * KG_TO_GEV;
+/// Conversion of a rate per nucleus per unit recoil energy (dimensionless in
+/// natural units) into events / kg / day / keV, up to a factor of 1 / m_N in
+/// GeV: (1 kg in GeV) (1 GeV in s^-1) (1 day in s) (1 keV in GeV).
+pub const RATE_TO_PER_KG_DAY_KEV: f64 = KG_TO_GEV * GEV_TO_INV_S * 86_400.0 * 1e-6;
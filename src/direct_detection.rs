@@ -0,0 +1,259 @@
+//! Common pieces needed to compute direct-detection cross sections and
+//! rates: nucleon matrix elements, target nuclei, nuclear form factors, the
+//! standard halo model and the rescaling of the cross section by the
+//! fraction of the DM made up by the relic.
+
+use crate::constants::{FM_TO_INV_GEV, OMEGA_OBS_H2, SPEED_OF_LIGHT};
+use std::f64::consts::PI;
+
+/// Proton mass in GeV.
+pub const PROTON_MASS: f64 = 0.938_272;
+/// Neutron mass in GeV.
+pub const NEUTRON_MASS: f64 = 0.939_565;
+/// Atomic mass unit in GeV.
+pub const ATOMIC_MASS_UNIT: f64 = 0.931_494;
+
+/// Scalar matrix elements f_Tq = <N|m_q qbar q|N> / m_N of the light quarks
+/// in a nucleon of mass `mass`.
+#[derive(Clone, Copy, Debug)]
+pub struct NucleonMatrixElements {
+    /// Mass of the nucleon.
+    pub mass: f64,
+    /// Up-quark matrix element.
+    pub f_tu: f64,
+    /// Down-quark matrix element.
+    pub f_td: f64,
+    /// Strange-quark matrix element.
+    pub f_ts: f64,
+}
+
+impl NucleonMatrixElements {
+    pub fn new(mass: f64, f_tu: f64, f_td: f64, f_ts: f64) -> NucleonMatrixElements {
+        NucleonMatrixElements {
+            mass,
+            f_tu,
+            f_td,
+            f_ts,
+        }
+    }
+    /// Default proton matrix elements.
+    pub fn proton() -> NucleonMatrixElements {
+        NucleonMatrixElements::new(PROTON_MASS, 0.0153, 0.0191, 0.0447)
+    }
+    /// Default neutron matrix elements.
+    pub fn neutron() -> NucleonMatrixElements {
+        NucleonMatrixElements::new(NEUTRON_MASS, 0.0110, 0.0273, 0.0447)
+    }
+    /// Effective Higgs-nucleon coupling f_N = 2/9 + 7/9 sum_q f_Tq, where the
+    /// heavy quarks contribute through the gluon operator.
+    pub fn f_n(&self) -> f64 {
+        2.0 / 9.0 + 7.0 / 9.0 * (self.f_tu + self.f_td + self.f_ts)
+    }
+}
+
+/// Reduced mass of the DM-nucleus system.
+pub fn reduced_mass(mx: f64, mn: f64) -> f64 {
+    mx * mn / (mx + mn)
+}
+
+/// Rescale a direct-detection cross section by the fraction of the DM
+/// density made up by a relic with density `omega_h2`.
+pub fn rescale_by_relic_density(sigma: f64, omega_h2: f64) -> f64 {
+    sigma * omega_h2 / OMEGA_OBS_H2
+}
+
+/// Target nucleus of a direct-detection experiment.
+#[derive(Clone, Copy, Debug)]
+pub struct Target {
+    /// Name of the target.
+    pub name: &'static str,
+    /// Mass number.
+    pub a: f64,
+    /// Atomic number.
+    pub z: f64,
+    /// Coupling to the nuclear magnetic moment, Σ_i f_i λ_i^2 (J_i + 1) / (3 J_i),
+    /// summed over the isotopes with natural abundance f_i, spin J_i and
+    /// magnetic moment λ_i in nuclear magnetons.
+    pub magnetic: f64,
+}
+
+impl Target {
+    pub fn new(name: &'static str, a: f64, z: f64) -> Target {
+        Target {
+            name,
+            a,
+            z,
+            magnetic: 0.0,
+        }
+    }
+    /// Set the coupling to the nuclear magnetic moment.
+    pub fn with_magnetic(mut self, magnetic: f64) -> Target {
+        self.magnetic = magnetic;
+        self
+    }
+    /// Natural xenon, with the magnetic moments of 129Xe and 131Xe.
+    pub fn xenon() -> Target {
+        Target::new("Xe", 131.0, 54.0).with_magnetic(0.2162)
+    }
+    /// Natural germanium, with the magnetic moment of 73Ge.
+    pub fn germanium() -> Target {
+        Target::new("Ge", 73.0, 32.0).with_magnetic(0.02445)
+    }
+    pub fn argon() -> Target {
+        Target::new("Ar", 40.0, 18.0)
+    }
+    pub fn iodine() -> Target {
+        Target::new("I", 127.0, 53.0).with_magnetic(3.694)
+    }
+    pub fn sodium() -> Target {
+        Target::new("Na", 23.0, 11.0).with_magnetic(2.732)
+    }
+    /// Mass of the nucleus in GeV.
+    pub fn mass(&self) -> f64 {
+        self.a * ATOMIC_MASS_UNIT
+    }
+    /// Helm form factor of the nucleus for recoil energy `er` in GeV.
+    pub fn helm_form_factor(&self, er: f64) -> f64 {
+        let q = (2.0 * self.mass() * er).sqrt();
+        if q <= 0.0 {
+            return 1.0;
+        }
+        let a = 0.52 * FM_TO_INV_GEV;
+        let s = 0.9 * FM_TO_INV_GEV;
+        let c = (1.23 * self.a.powf(1.0 / 3.0) - 0.6) * FM_TO_INV_GEV;
+        let rn = (c * c + 7.0 / 3.0 * PI * PI * a * a - 5.0 * s * s).sqrt();
+        let qr = q * rn;
+        let j1 = (qr.sin() - qr * qr.cos()) / (qr * qr);
+        3.0 * j1 / qr * (-(q * s).powi(2) / 2.0).exp()
+    }
+}
+
+/// Standard halo model: a truncated Maxwell-Boltzmann distribution in the
+/// galactic frame, boosted into the Earth frame. All velocities are in
+/// units of c.
+#[derive(Clone, Copy, Debug)]
+pub struct StandardHaloModel {
+    /// Local DM density in GeV / cm^3.
+    pub rho: f64,
+    /// Most probable speed.
+    pub v0: f64,
+    /// Galactic escape speed.
+    pub vesc: f64,
+    /// Speed of the Earth in the galactic frame.
+    pub vearth: f64,
+}
+
+impl Default for StandardHaloModel {
+    fn default() -> StandardHaloModel {
+        StandardHaloModel {
+            rho: 0.3,
+            v0: 220.0 / SPEED_OF_LIGHT,
+            vesc: 544.0 / SPEED_OF_LIGHT,
+            vearth: 232.0 / SPEED_OF_LIGHT,
+        }
+    }
+}
+
+impl StandardHaloModel {
+    /// Speed distribution f(v) in the Earth frame, normalized so that
+    /// ∫ f(v) dv = 1.
+    pub fn speed_distribution(&self, v: f64) -> f64 {
+        let (v0, vesc, ve) = (self.v0, self.vesc, self.vearth);
+        let z = vesc / v0;
+        let norm = erf(z) - 2.0 * z * (-z * z).exp() / PI.sqrt();
+        let pre = v / (norm * ve * v0 * PI.sqrt());
+        if v < vesc - ve {
+            pre * ((-(v - ve).powi(2) / (v0 * v0)).exp() - (-(v + ve).powi(2) / (v0 * v0)).exp())
+        } else if v < vesc + ve {
+            pre * ((-(v - ve).powi(2) / (v0 * v0)).exp() - (-z * z).exp())
+        } else {
+            0.0
+        }
+    }
+    /// Compute the velocity integral ∫_{vmin} v^n f(v) dv.
+    pub fn velocity_moment(&self, vmin: f64, n: i32) -> f64 {
+        let vmax = self.vesc + self.vearth;
+        if vmin >= vmax {
+            return 0.0;
+        }
+        // Simpson's rule
+        let npts = 2000;
+        let h = (vmax - vmin) / npts as f64;
+        let g = |v: f64| v.powi(n) * self.speed_distribution(v);
+        let mut sum = g(vmin) + g(vmax);
+        for i in 1..npts {
+            let wgt = if i % 2 == 1 { 4.0 } else { 2.0 };
+            sum += wgt * g(vmin + i as f64 * h);
+        }
+        sum * h / 3.0
+    }
+    /// Mean inverse speed η(vmin) = ∫_{vmin} f(v) / v dv.
+    pub fn eta(&self, vmin: f64) -> f64 {
+        self.velocity_moment(vmin, -1)
+    }
+    /// Mean speed above vmin, ∫_{vmin} v f(v) dv.
+    pub fn mean_speed(&self, vmin: f64) -> f64 {
+        self.velocity_moment(vmin, 1)
+    }
+}
+
+/// Minimum DM speed needed to produce a recoil of energy `er` off a nucleus
+/// of mass `mn` while up-scattering into a state heavier by `delta`.
+pub fn vmin_inelastic(mx: f64, mn: f64, er: f64, delta: f64) -> f64 {
+    let mu = reduced_mass(mx, mn);
+    (mn * er / mu + delta) / (2.0 * mn * er).sqrt()
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, |error| < 1.5e-7).
+pub fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x >= 0.0 {
+        y
+    } else {
+        -y
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_erf() {
+        for &(x, val) in &[
+            (0.5, 0.520_499_877_8),
+            (1.0, 0.842_700_792_9),
+            (2.0, 0.995_322_265_0),
+        ] {
+            assert!((erf(x) - val).abs() < 1.5e-7, "erf({}) = {}", x, erf(x));
+            assert!((erf(-x) + val).abs() < 1.5e-7, "erf({}) = {}", -x, erf(-x));
+        }
+        assert!(erf(0.0).abs() < 1.5e-7);
+    }
+
+    /// The Helm form factor of xenon (r_n = 5.837 fm) goes to one at small
+    /// momentum transfer and has its first zero at q r_n = 4.4934.
+    #[test]
+    fn test_helm_form_factor() {
+        let xe = Target::xenon();
+        let rn = 5.8365 * FM_TO_INV_GEV;
+        let s = 0.9 * FM_TO_INV_GEV;
+
+        let er = 1e-6;
+        let q = (2.0 * xe.mass() * er).sqrt();
+        let x = q * rn;
+        let taylor = (1.0 - x * x / 10.0 + x.powi(4) / 280.0) * (-(q * s).powi(2) / 2.0).exp();
+        assert!((xe.helm_form_factor(er) - taylor).abs() < 1e-5);
+
+        let q0 = 4.4934 / rn;
+        let er0 = q0 * q0 / (2.0 * xe.mass());
+        assert!(xe.helm_form_factor(er0).abs() < 1e-3);
+        assert!(xe.helm_form_factor(0.9 * er0) > 0.0);
+        assert!(xe.helm_form_factor(1.1 * er0) < 0.0);
+    }
+}
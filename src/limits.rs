@@ -0,0 +1,262 @@
+//! Comparison of parameter points with experimental exclusion curves. Each
+//! curve, e.g. σ_SI(m) from XENON/LZ or <σv>(m) from Fermi-LAT, is loaded
+//! from a local CSV file with two columns (DM mass, limit) and interpolated
+//! linearly in log-log space. A scan point is excluded by a curve if its
+//! value of the corresponding observable lies above the curve.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::path::Path;
+
+/// Exclusion curve for a single observable.
+#[derive(Clone, Debug)]
+pub struct ExclusionCurve {
+    /// Name of the constraint, e.g. "LZ2022".
+    pub name: String,
+    /// Name of the observable which is constrained, e.g. "sigma_si".
+    pub observable: String,
+    /// Logarithm of the DM masses.
+    log_masses: Vec<f64>,
+    /// Logarithm of the upper limits.
+    log_limits: Vec<f64>,
+}
+
+/// Parameter point of a scan along with its observables.
+#[derive(Clone, Debug)]
+pub struct ScanPoint {
+    /// Mass of the DM.
+    pub mass: f64,
+    /// Values of the observables, keyed by name.
+    pub observables: BTreeMap<String, f64>,
+}
+
+/// Collection of exclusion curves.
+#[derive(Clone, Debug, Default)]
+pub struct LimitSet {
+    pub curves: Vec<ExclusionCurve>,
+}
+
+impl ExclusionCurve {
+    /// Construct a curve from a list of masses and upper limits.
+    pub fn new(name: &str, observable: &str, masses: &[f64], limits: &[f64]) -> ExclusionCurve {
+        let mut points: Vec<(f64, f64)> = masses
+            .iter()
+            .zip(limits.iter())
+            .filter(|(m, l)| **m > 0.0 && **l > 0.0)
+            .map(|(m, l)| (m.ln(), l.ln()))
+            .collect();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        ExclusionCurve {
+            name: name.to_string(),
+            observable: observable.to_string(),
+            log_masses: points.iter().map(|p| p.0).collect(),
+            log_limits: points.iter().map(|p| p.1).collect(),
+        }
+    }
+    /// Load a curve from a CSV file with columns (mass, limit). Lines starting
+    /// with '#' are skipped and the first remaining line may be a header with
+    /// non-numeric column names. Any other line which can't be parsed is
+    /// reported as an error.
+    pub fn from_csv<P: AsRef<Path>>(
+        path: P,
+        name: &str,
+        observable: &str,
+    ) -> std::io::Result<ExclusionCurve> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut masses = Vec::new();
+        let mut limits = Vec::new();
+        let mut first = true;
+        for (lineno, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let cols: Vec<&str> = line.split(&[',', ';'][..]).collect();
+            let parsed: Vec<Option<f64>> = cols
+                .iter()
+                .take(2)
+                .map(|c| c.trim().parse::<f64>().ok())
+                .collect();
+            match parsed.as_slice() {
+                [Some(m), Some(l)] => {
+                    masses.push(*m);
+                    limits.push(*l);
+                }
+                [None, None] if first => {}
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "line {}: expected two numeric columns, found '{}'",
+                            lineno + 1,
+                            line
+                        ),
+                    ))
+                }
+            }
+            first = false;
+        }
+        if masses.len() < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "exclusion curve needs at least two points",
+            ));
+        }
+        Ok(ExclusionCurve::new(name, observable, &masses, &limits))
+    }
+    /// Interpolate the limit at DM mass `mass`. Returns `None` outside the
+    /// range of masses covered by the curve.
+    pub fn limit(&self, mass: f64) -> Option<f64> {
+        let n = self.log_masses.len();
+        if n < 2 || mass <= 0.0 {
+            return None;
+        }
+        let lm = mass.ln();
+        if lm < self.log_masses[0] || lm > self.log_masses[n - 1] {
+            return None;
+        }
+        let i = match self
+            .log_masses
+            .iter()
+            .position(|&m| m >= lm)
+            .unwrap_or(n - 1)
+        {
+            0 => 1,
+            i => i,
+        };
+        let (x0, x1) = (self.log_masses[i - 1], self.log_masses[i]);
+        let (y0, y1) = (self.log_limits[i - 1], self.log_limits[i]);
+        Some((y0 + (y1 - y0) * (lm - x0) / (x1 - x0)).exp())
+    }
+    /// Returns true if `value` at DM mass `mass` is excluded by the curve.
+    pub fn is_excluded(&self, mass: f64, value: f64) -> bool {
+        match self.limit(mass) {
+            Some(limit) => value > limit,
+            None => false,
+        }
+    }
+}
+
+impl ScanPoint {
+    pub fn new(mass: f64) -> ScanPoint {
+        ScanPoint {
+            mass,
+            observables: BTreeMap::new(),
+        }
+    }
+    /// Add an observable to the point.
+    pub fn with(mut self, name: &str, value: f64) -> ScanPoint {
+        self.observables.insert(name.to_string(), value);
+        self
+    }
+}
+
+impl LimitSet {
+    pub fn new() -> LimitSet {
+        LimitSet { curves: vec![] }
+    }
+    /// Add a curve to the set.
+    pub fn push(&mut self, curve: ExclusionCurve) {
+        self.curves.push(curve);
+    }
+    /// Determine whether `point` is excluded by each constraint. Constraints
+    /// on observables which the point doesn't have are skipped.
+    pub fn check(&self, point: &ScanPoint) -> BTreeMap<String, bool> {
+        self.curves
+            .iter()
+            .filter_map(|curve| {
+                point
+                    .observables
+                    .get(&curve.observable)
+                    .map(|&v| (curve.name.clone(), curve.is_excluded(point.mass, v)))
+            })
+            .collect()
+    }
+    /// Returns true if `point` is allowed by all the constraints.
+    pub fn is_allowed(&self, point: &ScanPoint) -> bool {
+        self.check(point).values().all(|excluded| !excluded)
+    }
+    /// Write a scan table with the mass, the observables and a column for
+    /// each constraint (1 if excluded, 0 otherwise) for each point. The
+    /// observable columns are the union of the observables of all points, and
+    /// entries for observables a point doesn't have are written as nan.
+    pub fn write_scan_table<P: AsRef<Path>>(
+        &self,
+        path: P,
+        points: &[ScanPoint],
+    ) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        let names: BTreeSet<&String> = points.iter().flat_map(|p| p.observables.keys()).collect();
+        let mut header = "# mass".to_string();
+        for name in names.iter() {
+            header.push_str(&format!(" {}", name));
+        }
+        for curve in self.curves.iter() {
+            header.push_str(&format!(" {}", curve.name));
+        }
+        writeln!(file, "{}", header)?;
+        for point in points.iter() {
+            let mut line = format!("{}", point.mass);
+            for name in names.iter() {
+                match point.observables.get(*name) {
+                    Some(value) => line.push_str(&format!(" {}", value)),
+                    None => line.push_str(" nan"),
+                }
+            }
+            for curve in self.curves.iter() {
+                line.push_str(match point.observables.get(&curve.observable) {
+                    Some(&v) if curve.is_excluded(point.mass, v) => " 1",
+                    Some(_) => " 0",
+                    None => " nan",
+                });
+            }
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_log_interpolation() {
+        let curve = ExclusionCurve::new("test", "sigma_si", &[10.0, 1000.0], &[1e-46, 1e-44]);
+        let limit = curve.limit(100.0).unwrap();
+        assert!((limit / 1e-45 - 1.0).abs() < 1e-10);
+        assert!(curve.is_excluded(100.0, 2e-45));
+        assert!(!curve.is_excluded(100.0, 5e-46));
+        assert!(curve.limit(1.0).is_none());
+    }
+
+    #[test]
+    fn test_scan_table_missing_observables() {
+        let mut limits = LimitSet::new();
+        let curve = ExclusionCurve::new("LZ", "sigma_si", &[10.0, 1e3], &[1.0, 1e2]);
+        limits.push(curve);
+        let points = vec![
+            ScanPoint::new(100.0).with("omega", 0.1),
+            ScanPoint::new(200.0).with("sigma_si", 50.0),
+        ];
+        let path = std::env::temp_dir().join("test_scan_table_missing_observables.dat");
+        limits.write_scan_table(&path, &points).unwrap();
+        let table = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "# mass omega sigma_si LZ");
+        assert_eq!(lines[1], "100 0.1 nan nan");
+        assert_eq!(lines[2], "200 nan 50 1");
+    }
+
+    #[test]
+    fn test_csv_parse_errors() {
+        let path = std::env::temp_dir().join("test_csv_parse_errors.csv");
+        std::fs::write(&path, "mass,limit\n10,1e-46\n100,1e-45\n").unwrap();
+        assert!(ExclusionCurve::from_csv(&path, "test", "sigma_si").is_ok());
+        std::fs::write(&path, "mass,limit\n10,1e-46\n100,le-45\n1000,1e-44\n").unwrap();
+        assert!(ExclusionCurve::from_csv(&path, "test", "sigma_si").is_err());
+    }
+}
@@ -1,5 +1,11 @@
 pub mod boltz;
+pub mod bound_state;
+pub mod constants;
+pub mod direct_detection;
+pub mod limits;
 pub mod models;
+pub mod observables;
+pub mod sommerfeld;
 pub mod utils;
 
 use boltz::*;
@@ -15,7 +21,7 @@ fn simple() -> std::io::Result<()> {
     let model = ScalarSinglet::new(ms, lam);
 
     let mut file = std::fs::File::create("analysis/simple_boltz_data.dat")?;
-    let sol = integrate_simple_boltzmann(model, 1.0, 1000.0);
+    let sol = integrate_simple_boltzmann(model, &StandardCosmology, 1.0, 1000.0);
     let rd = sol.us[sol.us.len() - 1][0].exp() * ms * S_TODAY / RHO_CRIT;
     println!("retcode = {:?}", sol.retcode);
     println!("rd = {}", rd);
@@ -34,8 +40,12 @@ fn full() -> std::io::Result<()> {
     let lam = 1e-3;
     let model = ScalarSinglet::new(ms, lam);
 
-    let sol = integrate_full_boltzmann(model, 100, (15.0, 100.0));
+    let result = integrate_full_boltzmann(model, &StandardCosmology, 100, (15.0, 100.0));
+    let (sol, report) = (result.sol, result.report);
     println!("retcode = {:?}", sol.retcode);
+    println!("number residual = {:e}", report.max_number_residual());
+    let tight = report.max_tight_coupling_deviation(1e3);
+    println!("tight coupling = {:e}", tight);
     let mut file = std::fs::File::create("analysis/full_boltz_data.dat")?;
     for (t, u) in sol {
         let mut string = format!("{} {}\n", t, u).to_string();
@@ -53,7 +63,7 @@ fn full_toy() -> std::io::Result<()> {
         c1: 1e-8,
     };
 
-    let sol = integrate_full_boltzmann(model, 100, (15.0, 100.0));
+    let sol = integrate_full_boltzmann(model, &StandardCosmology, 100, (15.0, 100.0)).sol;
     println!("retcode = {:?}", sol.retcode);
     let mut file = std::fs::File::create("analysis/full_boltz_data.dat")?;
     for (t, u) in sol {
@@ -67,7 +77,7 @@ fn full_toy() -> std::io::Result<()> {
 fn full_dipole() -> std::io::Result<()> {
     let model = DipoleDm::new(100.0, 1.0, 1e6, 1.0, 1.0);
 
-    let sol = integrate_full_boltzmann(model, 100, (1.0, 100.0));
+    let sol = integrate_full_boltzmann(model, &StandardCosmology, 100, (1.0, 100.0)).sol;
     println!("retcode = {:?}", sol.retcode);
     let mut file = std::fs::File::create("analysis/full_boltz_data.dat")?;
     for (t, u) in sol {
@@ -78,6 +88,18 @@ fn full_dipole() -> std::io::Result<()> {
     Ok(())
 }
 
+#[allow(dead_code)]
+fn asymmetric() -> std::io::Result<()> {
+    let model = EftDm::dipole(100.0, 1e4, 1.0, 1.0);
+    let eta = 1e-12;
+
+    let res = integrate_asymmetric_boltzmann(model, &StandardCosmology, eta, 1.0, 1000.0);
+    println!("retcode = {:?}", res.sol.retcode);
+    println!("rd sym = {}", res.omega_sym);
+    println!("rd asym = {}", res.omega_asym);
+    Ok(())
+}
+
 fn main() -> std::io::Result<()> {
     let now = Instant::now();
     let ret = {
@@ -85,6 +107,7 @@ fn main() -> std::io::Result<()> {
         //full()
         full_toy()
         //full_dipole()
+        //asymmetric()
     };
     println!("time = {}", now.elapsed().as_secs_f64());
     ret
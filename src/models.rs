@@ -1,6 +1,20 @@
+use crate::boltz::traits::Statistics;
+use crate::bound_state::BoundState;
+use crate::sommerfeld::Sommerfeld;
+use crate::utils::interpolation::LogSpline;
+use haliax_constants::prelude::*;
+use std::sync::Arc;
+
+pub mod dark_photon;
 pub mod dipole_dm;
+pub mod eft_dm;
+pub mod fermion_higgs_portal;
+pub mod inert_doublet;
 pub mod scalar_singlet;
+pub mod tabulated;
 pub mod toy;
+pub mod vector_higgs_portal;
+pub mod zprime_portal;
 
 /// Toy model that is used to check that the implementation of the full
 /// Boltzmann equation is valid.
@@ -21,6 +35,112 @@ pub struct ScalarSinglet {
     pub ms: f64,
     /// Coefficient of the SSHH term in the scalar potential.
     pub lam_hs: f64,
+    /// Sommerfeld enhancement from a light mediator, if any.
+    pub sommerfeld: Option<Sommerfeld>,
+    /// Bound state formed by exchanging a light mediator, if any.
+    pub bound_state: Option<BoundState>,
+}
+
+/// BSM model where the SM is altered by adding a massive vector gauge singlet
+/// which interacts with the SM through a Higgs interaction of the form
+///     Lint ~ λ_hv / 2 V_μV^μ H^†H.
+pub struct VectorHiggsPortal {
+    /// Mass of the new vector.
+    pub mv: f64,
+    /// Coefficient of the VVHH term.
+    pub lam_hv: f64,
+}
+
+/// BSM model where the SM is altered by adding a singlet Dirac or Majorana
+/// fermion which interacts with the SM through the dimension-5 operator
+///     Lint ~ -1/Λ (cosθ chibar.chi + sinθ chibar.iγ5.chi) H^†H.
+/// The scalar coupling (θ = 0) leads to p-wave annihilation and the
+/// pseudoscalar coupling (θ = π/2) to s-wave annihilation.
+pub struct FermionHiggsPortal {
+    /// Mass of the new fermion.
+    pub mx: f64,
+    /// Cut-off scale.
+    pub lam: f64,
+    /// CP-violating mixing angle between the scalar and pseudoscalar
+    /// couplings.
+    pub theta: f64,
+    /// True if the fermion is Majorana, false if it is Dirac.
+    pub majorana: bool,
+}
+
+/// Dirac fermion DM charged under a dark U(1) whose massive gauge boson A'
+/// kinetically mixes with hypercharge:
+///     L ~ -ε/2 F'_μν B^μν + g_d A'_μ chibar.γ^μ.chi.
+/// For m_A' << m_Z, the A' couples to the SM fermions as ε e cosθ_W Q_f.
+pub struct DarkPhoton {
+    /// Mass of the dark matter.
+    pub mx: f64,
+    /// Mass of the dark photon.
+    pub map: f64,
+    /// Dark fine-structure constant g_d^2 / 4π.
+    pub alpha_d: f64,
+    /// Kinetic mixing parameter.
+    pub eps: f64,
+    /// Decay width of the dark photon.
+    pub width_ap: f64,
+}
+
+/// Inert doublet model: the SM is extended by a second, Z2-odd Higgs doublet
+///     H2 = (H^+, (H0 + i A0) / √2)
+/// whose lightest neutral component H0 is the DM. The partners A0 and H^±
+/// coannihilate with H0. λ_L = (λ3 + λ4 + λ5) / 2 sets the hH0H0 coupling.
+pub struct InertDoublet {
+    /// Mass of the DM H0.
+    pub mh0: f64,
+    /// Mass splitting m_A0 - m_H0.
+    pub dma: f64,
+    /// Mass splitting m_H± - m_H0.
+    pub dmc: f64,
+    /// Higgs-portal coupling of H0.
+    pub lam_l: f64,
+    /// Tabulated cross sections into boson final states, if any.
+    tables: Option<Arc<inert_doublet::bosons::BosonTables>>,
+}
+
+/// Charges of the SM fermions under the Z' of a `ZPrimePortal`. Charges are
+/// generation universal and neutrinos only couple through their left-handed
+/// component.
+#[derive(Clone, Copy, Debug)]
+pub enum ZPrimeCharges {
+    /// Unit vector charge for all quarks and leptons.
+    Universal,
+    /// Vector charge B - L.
+    BMinusL,
+    /// Unit vector charge for the quarks only.
+    Leptophobic,
+    /// Arbitrary vector and axial charges for the quarks and the leptons.
+    Custom {
+        quark_v: f64,
+        quark_a: f64,
+        lepton_v: f64,
+        lepton_a: f64,
+    },
+}
+
+/// Dirac fermion DM coupled to the SM through a massive Z':
+///     L ~ Z'_μ chibar.γ^μ(g_v - g_a γ5).chi
+///       + g_zp Z'_μ fbar.γ^μ(q_v - q_a γ5).f,
+/// with the SM charges (q_v, q_a) set by `ZPrimeCharges`.
+pub struct ZPrimePortal {
+    /// Mass of the dark matter.
+    pub mx: f64,
+    /// Mass of the Z'.
+    pub mzp: f64,
+    /// Vector coupling of the DM to the Z'.
+    pub gx_v: f64,
+    /// Axial coupling of the DM to the Z'.
+    pub gx_a: f64,
+    /// Gauge coupling of the Z' to the SM fermions.
+    pub g_zp: f64,
+    /// Charges of the SM fermions.
+    pub charges: ZPrimeCharges,
+    /// Decay width of the Z'.
+    pub width_zp: f64,
 }
 
 /// Effective field theory with two dark matter particles chi1 and chi2 which
@@ -42,8 +162,97 @@ pub struct DipoleDm {
     pub width_h: f64,
     /// Coefficient of the s-channel contribution to gamma
     pub(super) gam_coeff_ss: f64,
-    /// Coefficient of the t-channel contribution to gamma
-    pub(super) gam_coeff_tt: f64,
-    /// Coefficient of the s-t interference contribution to gamma
-    pub(super) gam_coeff_st: f64,
+    /// Sommerfeld enhancement from a light mediator, if any.
+    pub sommerfeld: Option<Sommerfeld>,
+    /// Bound state formed by exchanging a light mediator, if any.
+    pub bound_state: Option<BoundState>,
+    /// Include production of chi1 from chi2 -> chi1 + γ decays.
+    pub late_decays: bool,
+}
+
+/// Wilson coefficients of the `EftDm` operators:
+///     L ~ c_M/(2Λ) chibar.σ_μν.chi F^μν + c_E/(2Λ) chibar.σ_μν.iγ5.chi F^μν
+///       + c_A/Λ^2 chibar.γ^μ.γ5.chi ∂^ν F_μν
+///       + c_R/Λ^2 chibar.γ^μ.chi ∂^ν F_μν
+///       + c_γ/Λ^3 chibar.chi F_μν F^μν
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EftCoefficients {
+    /// Magnetic dipole coefficient c_M.
+    pub magnetic: f64,
+    /// Electric dipole coefficient c_E.
+    pub electric: f64,
+    /// Anapole coefficient c_A.
+    pub anapole: f64,
+    /// Charge-radius coefficient c_R.
+    pub charge_radius: f64,
+    /// Rayleigh coefficient c_γ.
+    pub rayleigh: f64,
+}
+
+/// Dirac fermion DM interacting with the photon through the operators of
+/// `EftCoefficients` with cut-off scale `lam`. The dipole operators reduce to
+/// `DipoleDm` in the limit of a vanishing mass splitting.
+pub struct EftDm {
+    /// Mass of the dark matter.
+    pub mx: f64,
+    /// Cut-off scale.
+    pub lam: f64,
+    /// Wilson coefficients.
+    pub coeffs: EftCoefficients,
+}
+
+/// Model defined by a tabulated annihilation cross section σ(√s) and
+/// momentum exchange rate γ(T), e.g. produced by MadGraph or CalcHEP. The
+/// tables are interpolated with cubic splines in log-log space.
+pub struct TabulatedModel {
+    /// Mass of the dark-matter particle.
+    pub mx: f64,
+    /// Internal degrees of freedom of the dark-matter particle.
+    pub g: f64,
+    /// Quantum statistics of the dark-matter particle.
+    pub statistics: Statistics,
+    /// Annihilation cross section in GeV^-2 vs. center-of-mass energy in GeV.
+    pub(super) sigma: LogSpline,
+    /// Momentum exchange rate in GeV vs. SM temperature in GeV.
+    pub(super) gamma: LogSpline,
+}
+
+/// SM fermion which the DM can annihilate into or scatter off.
+#[derive(Clone, Copy, Debug)]
+pub struct SmFermion {
+    /// Name of the fermion-antifermion final state, e.g. "bb".
+    pub name: &'static str,
+    /// Mass of the fermion.
+    pub mass: f64,
+    /// Number of colors.
+    pub ncol: f64,
+    /// Electric charge.
+    pub charge: f64,
+    /// Weak isospin of the left-handed component.
+    pub t3: f64,
+}
+
+/// Table of the SM fermions, neglecting the neutrino masses.
+pub fn sm_fermions() -> Vec<SmFermion> {
+    let f = |name, mass, ncol, charge, t3| SmFermion {
+        name,
+        mass,
+        ncol,
+        charge,
+        t3,
+    };
+    vec![
+        f("tt", TOP_QUARK_MASS, 3.0, 2.0 / 3.0, 0.5),
+        f("cc", CHARM_QUARK_MASS, 3.0, 2.0 / 3.0, 0.5),
+        f("uu", UP_QUARK_MASS, 3.0, 2.0 / 3.0, 0.5),
+        f("bb", BOTTOM_QUARK_MASS, 3.0, -1.0 / 3.0, -0.5),
+        f("ss", STRANGE_QUARK_MASS, 3.0, -1.0 / 3.0, -0.5),
+        f("dd", DOWN_QUARK_MASS, 3.0, -1.0 / 3.0, -0.5),
+        f("tautau", TAU_MASS, 1.0, -1.0, -0.5),
+        f("mumu", MUON_MASS, 1.0, -1.0, -0.5),
+        f("ee", ELECTRON_MASS, 1.0, -1.0, -0.5),
+        f("nunu_tau", 0.0, 1.0, 0.0, 0.5),
+        f("nunu_mu", 0.0, 1.0, 0.0, 0.5),
+        f("nunu_e", 0.0, 1.0, 0.0, 0.5),
+    ]
 }
@@ -0,0 +1,111 @@
+pub mod gamma;
+pub mod sigma;
+pub mod width;
+
+use super::{sm_fermions, DarkPhoton, SmFermion};
+use crate::boltz::traits::{fermion_momentum_exchange_rate, DarkMatterModel, Statistics};
+use crate::observables::Annihilation;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+/// SM fermions which couple to the dark photon through their electric
+/// charge.
+pub(super) fn charged_fermions() -> Vec<SmFermion> {
+    sm_fermions()
+        .into_iter()
+        .filter(|f| f.charge != 0.0)
+        .collect()
+}
+
+impl DarkPhoton {
+    pub fn new(mx: f64, map: f64, alpha_d: f64, eps: f64) -> DarkPhoton {
+        DarkPhoton {
+            mx,
+            map,
+            alpha_d,
+            eps,
+            width_ap: DarkPhoton::compute_width_ap(mx, map, alpha_d, eps),
+        }
+    }
+    /// Dark gauge coupling.
+    pub fn g_d(&self) -> f64 {
+        (4.0 * std::f64::consts::PI * self.alpha_d).sqrt()
+    }
+    /// Coupling of the dark photon to a SM fermion with electric charge `qf`.
+    pub fn kappa(&self, qf: f64) -> f64 {
+        DarkPhoton::kappa_f(self.eps, qf)
+    }
+    pub(super) fn kappa_f(eps: f64, qf: f64) -> f64 {
+        eps * (4.0 * std::f64::consts::PI * ALPHA_EM).sqrt() * COS_THETA_WEAK * qf
+    }
+}
+
+impl DarkMatterModel for DarkPhoton {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        2.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Fermion
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_xx(cme)
+    }
+    /// Compute the momentum exchange rate between the DM and the charged SM
+    /// fermions.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        fermion_momentum_exchange_rate(self.mx, self.g(), x, charged_fermions(), |f, w, t| {
+            self.elastic_amplitude_sqrd(w, t, f.mass, f.ncol, f.charge)
+        })
+    }
+    /// The A' resonance and the A'A' threshold.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![self.map / self.mx, 2.0 * self.map / self.mx]
+    }
+}
+
+impl Annihilation for DarkPhoton {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_xx_channels(cme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::observables::cme_from_vrel;
+    use std::f64::consts::PI;
+
+    /// On the A' pole the cross section is the Breit-Wigner peak
+    ///     σ = 12π / (s β^2) Γ(A' -> chi+chibar) Γ(A' -> f+fbar) / Γ^2.
+    #[test]
+    fn test_resonance_peak() {
+        let model = DarkPhoton::new(100.0, 300.0, 0.1, 1e-3);
+        let s = model.map * model.map;
+        let beta2 = 1.0 - 4.0 * model.mx * model.mx / s;
+        let width_in = DarkPhoton::width_v_to_ff(model.map, model.g_d(), model.mx, 1.0);
+        for f in charged_fermions()
+            .iter()
+            .filter(|f| 2.0 * f.mass < model.map)
+        {
+            let width_out =
+                DarkPhoton::width_v_to_ff(model.map, model.kappa(f.charge), f.mass, f.ncol);
+            let expected = 12.0 * PI / (s * beta2) * width_in * width_out / model.width_ap.powi(2);
+            let sigma = model.sigma_xx_ff(model.map, f.mass, f.ncol, f.charge);
+            assert!((sigma - expected).abs() < 1e-10 * expected, "{}", f.name);
+        }
+    }
+
+    /// For a light A' the annihilation into A'A' reduces to the QED result
+    /// σv = π α_d^2 / m^2 at small velocities.
+    #[test]
+    fn test_secluded_limit() {
+        let model = DarkPhoton::new(100.0, 0.1, 0.05, 1e-6);
+        let vrel = 1e-3;
+        let sigmav = model.sigma_xx_apap(cme_from_vrel(model.mx, vrel)) * vrel;
+        let expected = PI * model.alpha_d.powi(2) / model.mx.powi(2);
+        assert!((sigmav / expected - 1.0).abs() < 1e-3, "σv = {:e}", sigmav);
+    }
+}
@@ -0,0 +1,24 @@
+use super::DarkPhoton;
+
+impl DarkPhoton {
+    /// Squared matrix element for chi+f -> chi+f through t-channel A'
+    /// exchange, summed over the spins and colors of all particles, for a
+    /// SM fermion with energy `w` in the DM rest frame.
+    pub(super) fn elastic_amplitude_sqrd(
+        &self,
+        w: f64,
+        t: f64,
+        mf: f64,
+        ncol: f64,
+        qf: f64,
+    ) -> f64 {
+        let m2 = self.mx * self.mx;
+        let mf2 = mf * mf;
+        let s = m2 + mf2 + 2.0 * self.mx * w;
+        let u = 2.0 * (m2 + mf2) - s - t;
+        8.0 * ncol
+            * (self.g_d() * self.kappa(qf)).powi(2)
+            * ((s - m2 - mf2).powi(2) + (u - m2 - mf2).powi(2) + 2.0 * t * (m2 + mf2))
+            / (t - self.map * self.map).powi(2)
+    }
+}
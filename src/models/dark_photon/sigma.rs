@@ -0,0 +1,85 @@
+use super::{charged_fermions, DarkPhoton};
+use crate::utils::integration::*;
+use std::collections::BTreeMap;
+
+impl DarkPhoton {
+    /// Compute the cross section for chi+chibar -> A'^* -> f+fbar for a SM
+    /// fermion with mass `mf`, `ncol` colors and electric charge `qf`.
+    pub fn sigma_xx_ff(&self, cme: f64, mf: f64, ncol: f64, qf: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let mf2 = mf * mf;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_f = (1.0 - 4.0 * mf2 / s).sqrt();
+            let ma2 = self.map * self.map;
+            let prop = (s - ma2).powi(2) + ma2 * self.width_ap.powi(2);
+            ncol * (self.g_d() * self.kappa(qf)).powi(2)
+                * s
+                * (1.0 + 2.0 * m2 / s)
+                * (1.0 + 2.0 * mf2 / s)
+                * beta_f
+                / (12.0 * std::f64::consts::PI * beta * prop)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for chi+chibar -> A' + A' through t- and
+    /// u-channel DM exchange (the secluded regime.)
+    pub fn sigma_xx_apap(&self, cme: f64) -> f64 {
+        if cme > 2.0 * self.map && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let v2 = self.map * self.map;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_f = (1.0 - 4.0 * v2 / s).sqrt();
+            let e2 = s / 4.0;
+            // |p_i| |p_f|
+            let pp = e2 * beta * beta_f;
+
+            let mut sum = 0.0;
+            for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+                let t = m2 + v2 - 2.0 * (e2 - pp * z);
+                let u = m2 + v2 - 2.0 * (e2 + pp * z);
+                sum += wgt * self.amplitude_sqrd_xx_apap(t, u);
+            }
+            // Average over initial spins and include symmetry factor of 1/2
+            // for the identical final states.
+            self.g_d().powi(4) * beta_f * sum / (256.0 * std::f64::consts::PI * s * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Squared matrix element for chi+chibar -> A' + A' summed over spins and
+    /// polarizations, with g_d = 1.
+    fn amplitude_sqrd_xx_apap(&self, t: f64, u: f64) -> f64 {
+        let m2 = self.mx * self.mx;
+        let v2 = self.map * self.map;
+        let m4 = m2 * m2;
+        let v4 = v2 * v2;
+        let num = 6.0 * v4 * m4 - 6.0 * v4 * m2 * (t + u) - v4 * (t * t + u * u) + 8.0 * v4 * t * u
+            - 4.0 * v2 * m4 * (t + u)
+            + 16.0 * v2 * m2 * t * u
+            - 4.0 * v2 * t * u * (t + u)
+            - 6.0 * m4 * m4
+            + 3.0 * m4 * (t * t + u * u)
+            + 14.0 * m4 * t * u
+            - m2 * (t.powi(3) + u.powi(3))
+            - 7.0 * m2 * t * u * (t + u)
+            + t * u * (t * t + u * u);
+        8.0 * num / ((t - m2) * (u - m2)).powi(2)
+    }
+    /// Compute the total annihilation cross section.
+    pub fn sigma_xx(&self, cme: f64) -> f64 {
+        self.sigma_xx_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_xx_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        for f in charged_fermions() {
+            channels.insert(f.name, self.sigma_xx_ff(cme, f.mass, f.ncol, f.charge));
+        }
+        channels.insert("A'A'", self.sigma_xx_apap(cme));
+        channels
+    }
+}
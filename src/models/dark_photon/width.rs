@@ -0,0 +1,28 @@
+use super::{charged_fermions, DarkPhoton};
+
+impl DarkPhoton {
+    /// Compute the width of a vector with mass `mv` into a fermion pair with
+    /// mass `mf`, `ncol` colors and vector coupling `g`.
+    pub(super) fn width_v_to_ff(mv: f64, g: f64, mf: f64, ncol: f64) -> f64 {
+        if mv > 2.0 * mf {
+            let r = mf * mf / (mv * mv);
+            ncol * g * g * mv / (12.0 * std::f64::consts::PI)
+                * (1.0 + 2.0 * r)
+                * (1.0 - 4.0 * r).sqrt()
+        } else {
+            0.0
+        }
+    }
+    /// Compute the total width of the dark photon into DM and SM fermions.
+    pub(super) fn compute_width_ap(mx: f64, map: f64, alpha_d: f64, eps: f64) -> f64 {
+        let g_d = (4.0 * std::f64::consts::PI * alpha_d).sqrt();
+        let width_dm = DarkPhoton::width_v_to_ff(map, g_d, mx, 1.0);
+        let width_sm: f64 = charged_fermions()
+            .iter()
+            .map(|f| {
+                DarkPhoton::width_v_to_ff(map, DarkPhoton::kappa_f(eps, f.charge), f.mass, f.ncol)
+            })
+            .sum();
+        width_dm + width_sm
+    }
+}
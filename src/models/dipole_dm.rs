@@ -1,12 +1,17 @@
+pub mod direct_detection;
 pub mod gamma;
 pub mod sigma;
 pub mod width;
 
 use super::DipoleDm;
-use crate::boltz::helper::hubblet;
-use crate::boltz::traits::FullBoltzmann;
+use crate::boltz::decay::TwoBodyDecay;
+use crate::boltz::traits::{DarkMatterModel, Statistics};
+use crate::bound_state::BoundState;
+use crate::observables::Annihilation;
+use crate::sommerfeld::{Sommerfeld, SommerfeldApprox};
 use cyphus_integration::prelude::*;
 use gamma::*;
+use std::collections::BTreeMap;
 
 impl DipoleDm {
     pub fn new(mx: f64, dm: f64, lam: f64, ce: f64, cm: f64) -> DipoleDm {
@@ -34,14 +39,50 @@ impl DipoleDm {
             cm,
             width_h,
             gam_coeff_ss: gamma_integrand_ss_coeff(mx, udm, ulam, ce, cm),
-            gam_coeff_tt: gamma_integrand_ss_coeff(mx, udm, ulam, ce, cm),
-            gam_coeff_st: gamma_integrand_ss_coeff(mx, udm, ulam, ce, cm),
+            sommerfeld: None,
+            bound_state: None,
+            late_decays: false,
         }
     }
+    /// Include the Sommerfeld enhancement from a mediator with mass
+    /// `mediator_mass` and coupling `alpha` to the dark matter.
+    pub fn with_sommerfeld(
+        mut self,
+        alpha: f64,
+        mediator_mass: f64,
+        approx: SommerfeldApprox,
+    ) -> DipoleDm {
+        self.sommerfeld = Some(Sommerfeld::new(alpha, mediator_mass, approx).tabulate(self.mx));
+        self
+    }
+    /// Include bound-state formation and decay as an additional depletion
+    /// channel.
+    pub fn with_bound_state(mut self, bound_state: BoundState) -> DipoleDm {
+        self.bound_state = Some(bound_state);
+        self
+    }
+    /// Include the production of chi1 from decays chi2 -> chi1 + γ, treating
+    /// chi2 as being in equilibrium with the bath.
+    pub fn with_late_decays(mut self) -> DipoleDm {
+        self.late_decays = true;
+        self
+    }
 }
 
-impl FullBoltzmann for DipoleDm {
-    fn gamma_hinv(&self, x: f64) -> f64 {
+impl DarkMatterModel for DipoleDm {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        2.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Fermion
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_11_to_gg(cme)
+    }
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
         let pre = 1.0 / (48.0 * (std::f64::consts::PI * self.mx).powi(3) * 2.0 * self.mx / x);
         let f = |w: f64| self.gamma_integrand(w, x);
         let gk_gamma = GaussKronrodIntegratorBuilder::default()
@@ -50,46 +91,34 @@ impl FullBoltzmann for DipoleDm {
             .key(2)
             .singular_points(vec![1.0])
             .build();
-        let gam = gk_gamma.integrate(&f, 0.0, f64::INFINITY).val;
-
-        pre * gam / hubblet(self.mx / x)
+        pre * gk_gamma.integrate(&f, 0.0, f64::INFINITY).val
     }
-    fn feq(&self, x: f64, q: f64) -> f64 {
-        let e = (q * q + x * x).sqrt(); // energy / temperature
-        1.0 / (e.exp() + 1.0)
+    fn sommerfeld(&self) -> Option<&Sommerfeld> {
+        self.sommerfeld.as_ref()
     }
-    fn sigmav(&self, x: f64, q: f64, qt: f64) -> f64 {
-        let temp = self.mx / x;
-        let k = temp * q;
-        let kt = temp * qt;
-        let gk_sig = GaussKronrodIntegratorBuilder::default()
-            .epsrel(1e-8)
-            .epsabs(0.0)
-            .key(2)
-            .build();
-        let f = |theta: f64| -> f64 {
-            let e1 = (k * k + self.mx * self.mx).sqrt();
-            let e2 = (kt * kt + self.mx * self.mx).sqrt();
-            // (E1;k).(E2;kt)
-            let dot = e1 * e2 - k * kt * theta;
-
-            let vmol = (dot * dot - self.mx.powi(4)).sqrt() / (e1 * e2);
-            let cme = 2.0 * self.mx * self.mx + 2.0 * dot;
-            self.sigma_11_to_gg(cme) * vmol
-        };
-        gk_sig.integrate(f, -1.0, 1.0).val / 2.0
+    fn bound_state(&self) -> Option<BoundState> {
+        self.bound_state
     }
-    fn dm_mass(&self) -> f64 {
-        self.mx
+    fn decays(&self) -> Vec<TwoBodyDecay> {
+        if self.late_decays {
+            vec![TwoBodyDecay::new(self.mx + self.dm, 2.0, self.width_h, 0.0)]
+        } else {
+            vec![]
+        }
     }
-    fn g(&self) -> f64 {
-        2.0
+}
+
+impl Annihilation for DipoleDm {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_11_channels(cme)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::direct_detection::*;
+    use haliax_constants::electroweak::ALPHA_EM;
 
     #[test]
     fn test_gamma() {
@@ -107,4 +136,48 @@ mod test {
         println!("{:e}", model.width_h);
         println!("{:e}", gam.val);
     }
+
+    /// The momentum exchange rate comes from the s-channel term, whose
+    /// coefficient scales as δm^6.
+    #[test]
+    fn test_gamma_coefficient() {
+        let (mx, dm) = (100.0, 0.1);
+        let m1 = DipoleDm::new(mx, dm, 1e4, 1.0, 1.0);
+        let m2 = DipoleDm::new(mx, 2.0 * dm, 1e4, 1.0, 1.0);
+        assert!((m2.gam_coeff_ss / m1.gam_coeff_ss / 64.0 - 1.0).abs() < 1e-12);
+        assert!(m1.momentum_exchange_rate(10.0) > 0.0);
+    }
+
+    /// At v = vmin the transverse velocity vanishes, so only the q^2 / (4 m_χ^2)
+    /// piece of the charge-dipole term survives.
+    #[test]
+    fn test_inelastic_threshold() {
+        let model = DipoleDm::new(100.0, 1e-4, 1e4, 0.0, 1.0);
+        let target = Target::iodine().with_magnetic(0.0);
+        let (mn, er) = (target.mass(), 3e-5);
+        let vmin = vmin_inelastic(model.mx, mn, er, model.dm);
+        let (a, b) = model.dsigma_der_coeffs(&target, er);
+        let expected = ALPHA_EM
+            * (model.cm / model.lam).powi(2)
+            * target.z.powi(2)
+            * target.helm_form_factor(er).powi(2)
+            * mn
+            / (2.0 * model.mx.powi(2));
+        assert!(((a * vmin * vmin + b) / expected - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_inelastic_rate_positive() {
+        let model = DipoleDm::new(70.0, 1.117e-4, 1e4, 0.0, 1.0);
+        let halo = StandardHaloModel::default();
+        let target = Target::iodine();
+        // Run past the kinematic endpoint, where the velocity integrals vanish
+        let rates: Vec<f64> = (1..=400)
+            .map(|i| model.differential_rate(&target, &halo, 0.5 * i as f64))
+            .collect();
+        let max = rates.iter().fold(0.0, |acc: f64, &r| acc.max(r));
+        assert!(max > 0.0);
+        assert!(rates.iter().all(|&r| r >= 0.0));
+        assert!(model.total_rate(&target, &halo, 1.0, 200.0) > 0.0);
+    }
 }
@@ -1,6 +1,7 @@
 use super::DipoleDm;
 use haliax_constants::electroweak::ALPHA_EM;
-use haliax_constants::masses::W_BOSON_MASS;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
 
 impl DipoleDm {
     /// Compute the annihilation cross-section for dark matter into photons.
@@ -39,7 +40,7 @@ impl DipoleDm {
         let temp29 = temp19 * temp5;
         let temp30 = 2.0 * temp17 * temp20;
         let temp31 = 2.0 * temp1 * temp28;
-        return ((self.ce * self.ce + self.cm + self.cm).powi(2)
+        return ((self.ce * self.ce + self.cm * self.cm).powi(2)
             * (96.0 * temp11 * temp12 * temp15
                 + q * temp16 * (temp18 + temp21 + temp25 + temp26) * (-q + temp5)
                 + q * temp16 * (temp18 + temp21 + temp25 + temp29) * (q + temp5)
@@ -207,6 +208,9 @@ impl DipoleDm {
         let temp6 = temp5 * temp5;
         let temp7 = -2.0 * temp3 * temp6;
         (ALPHA_EM
+            * ncol
+            * qf
+            * qf
             * (-4.0 * temp1 + temp2).sqrt()
             * (2.0 * temp1 + temp2)
             * (self.ce * self.ce * (temp4 + temp7 + temp2 * (-4.0 + temp3 - 4.0 * udm))
@@ -217,4 +221,39 @@ impl DipoleDm {
                 * (temp4 + temp3 * temp6 - 2.0 * temp2 * (2.0 + temp3 + 2.0 * udm)).sqrt()
                 * ulam.powi(2))
     }
+    /// Compute the annihilation cross section of the light dark matter into
+    /// each final state.
+    pub fn sigma_11_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        channels.insert("aa", self.sigma_11_to_gg(cme));
+        channels
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The photon final state depends on the dipoles only through
+    /// (c_E^2 + c_M^2)^2.
+    #[test]
+    fn test_sigma_11_to_gg() {
+        let sigma = |ce, cm| DipoleDm::new(100.0, 0.1, 1e4, ce, cm).sigma_11_to_gg(250.0);
+        let reference = sigma(0.0, 1.0);
+        assert!(reference > 0.0);
+        assert!((sigma(1.0, 0.0) / reference - 1.0).abs() < 1e-12);
+        assert!((sigma(1.0, 1.0) / reference - 4.0).abs() < 1e-12);
+        assert!((sigma(0.0, 2.0) / reference - 16.0).abs() < 1e-12);
+    }
+
+    /// chi1 + chi2 -> f + fbar proceeds through an s-channel photon, so it
+    /// scales as N_c Q_f^2.
+    #[test]
+    fn test_sigma_12_to_ff() {
+        let model = DipoleDm::new(100.0, 0.1, 1e4, 1.0, 1.0);
+        let lepton = model.sigma_12_to_ff(250.0, 1.0, 1.0, 1.0);
+        let up = model.sigma_12_to_ff(250.0, 1.0, 3.0, 2.0 / 3.0);
+        assert!(lepton > 0.0);
+        assert!((up / lepton - 4.0 / 3.0).abs() < 1e-12);
+    }
 }
@@ -0,0 +1,113 @@
+//! The anapole, charge-radius and dipole operators annihilate into SM
+//! fermions through an s-channel photon and scatter off them through a
+//! t-channel photon. The dipoles and the Rayleigh operator annihilate into
+//! photons, and the Rayleigh operator scatters off bath photons. Dipole
+//! Compton scattering, suppressed by an additional (c_M / Λ)^2, is neglected.
+
+pub mod gamma;
+pub mod sigma;
+
+use super::dark_photon::charged_fermions;
+use super::{EftCoefficients, EftDm};
+use crate::boltz::traits::{
+    elastic_gamma_integrand, fermion_momentum_exchange_rate, DarkMatterModel, Statistics,
+};
+use crate::observables::Annihilation;
+use cyphus_integration::prelude::*;
+use std::collections::BTreeMap;
+
+impl EftDm {
+    pub fn new(mx: f64, lam: f64, coeffs: EftCoefficients) -> EftDm {
+        EftDm { mx, lam, coeffs }
+    }
+    /// Construct the model with only the electric and magnetic dipole
+    /// operators, normalized as in `DipoleDm`. The DM is a single Dirac
+    /// fermion, so it only matches `DipoleDm` as the mass splitting goes to
+    /// zero.
+    pub fn dipole(mx: f64, lam: f64, ce: f64, cm: f64) -> EftDm {
+        EftDm::new(
+            mx,
+            lam,
+            EftCoefficients {
+                magnetic: cm,
+                electric: ce,
+                ..EftCoefficients::default()
+            },
+        )
+    }
+}
+
+impl DarkMatterModel for EftDm {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        2.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Fermion
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_xx(cme)
+    }
+    /// Compute the momentum exchange rate between the DM and SM.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        let fermions =
+            fermion_momentum_exchange_rate(self.mx, self.g(), x, charged_fermions(), |f, w, t| {
+                self.elastic_amplitude_sqrd(w, t, f.mass, f.ncol, f.charge)
+            });
+        let photons = if self.coeffs.rayleigh != 0.0 {
+            let gk_gamma = GaussKronrodIntegratorBuilder::default()
+                .epsrel(1e-8)
+                .epsabs(0.0)
+                .key(2)
+                .build();
+            let temp = self.mx / x;
+            let pre =
+                1.0 / (48.0 * std::f64::consts::PI.powi(3) * self.g() * self.mx.powi(3) * temp);
+            let f = |w: f64| {
+                elastic_gamma_integrand(w, temp, 0.0, Statistics::Boson, |t| {
+                    self.elastic_amplitude_sqrd_photon(t)
+                })
+            };
+            pre * gk_gamma.integrate(f, 0.0, f64::INFINITY).val
+        } else {
+            0.0
+        };
+        fermions + photons
+    }
+}
+
+impl Annihilation for EftDm {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_xx_channels(cme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::models::DipoleDm;
+    use haliax_constants::prelude::*;
+
+    /// The dipole operators must reproduce `DipoleDm` as the mass splitting
+    /// goes to zero. For δm / m = 1e-6 the difference is far below the
+    /// tolerance; at finite δm the two models differ at O(δm / m).
+    #[test]
+    fn test_dipole_limit() {
+        let (mx, lam, ce, cm) = (100.0, 1e4, 0.5, 2.0);
+        let dipole = DipoleDm::new(mx, 1e-4, lam, ce, cm);
+        let eft = EftDm::dipole(mx, lam, ce, cm);
+        for &cme in [210.0, 300.0, 1000.0].iter() {
+            let gg = eft.sigma_xx_gg(cme);
+            assert!((gg - dipole.sigma_11_to_gg(cme)).abs() < 1e-4 * gg);
+
+            let ee = eft.sigma_xx_ff(cme, ELECTRON_MASS, 1.0, -1.0);
+            assert!((ee - dipole.sigma_12_to_ff(cme, ELECTRON_MASS, 1.0, -1.0)).abs() < 1e-4 * ee);
+
+            let bb = eft.sigma_xx_ff(cme, BOTTOM_QUARK_MASS, 3.0, -1.0 / 3.0);
+            let bb_dipole = dipole.sigma_12_to_ff(cme, BOTTOM_QUARK_MASS, 3.0, -1.0 / 3.0);
+            assert!((bb - bb_dipole).abs() < 1e-4 * bb);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use super::EftDm;
+use haliax_constants::electroweak::ALPHA_EM;
+
+impl EftDm {
+    /// Squared matrix element for chi+f -> chi+f through t-channel photon
+    /// exchange, summed over the spins and colors of all particles, for a SM
+    /// fermion with energy `w` in the DM rest frame and electric charge `qf`.
+    pub(super) fn elastic_amplitude_sqrd(
+        &self,
+        w: f64,
+        t: f64,
+        mf: f64,
+        ncol: f64,
+        qf: f64,
+    ) -> f64 {
+        let m2 = self.mx * self.mx;
+        let mf2 = mf * mf;
+        let s = m2 + mf2 + 2.0 * self.mx * w;
+        let u = 2.0 * (m2 + mf2) - s - t;
+        let c = &self.coeffs;
+        let lam2 = self.lam * self.lam;
+
+        // The dipoles are long range, |M|^2 ~ 1/t.
+        let magnetic = -16.0
+            * ((m2 - mf2).powi(2) - 2.0 * m2 * (t + u) - mf2 * (t + 2.0 * u) + u * (t + u))
+            / t;
+        let electric =
+            -16.0 * ((m2 + mf2).powi(2) - 2.0 * m2 * u - mf2 * (t + 2.0 * u) + u * (t + u)) / t;
+        // The anapole and charge radius are contact interactions.
+        let anapole = 8.0
+            * (2.0 * (m2 - mf2).powi(2) - 4.0 * m2 * (t + u) - 4.0 * mf2 * u
+                + t * t
+                + 2.0 * t * u
+                + 2.0 * u * u);
+        let charge_radius = 8.0
+            * (2.0 * (m2 + mf2).powi(2) - 4.0 * (m2 + mf2) * u + t * t + 2.0 * t * u + 2.0 * u * u);
+        let int = 32.0 * self.mx * (2.0 * mf2 + t);
+
+        4.0 * std::f64::consts::PI
+            * ALPHA_EM
+            * ncol
+            * qf
+            * qf
+            * ((c.magnetic.powi(2) * magnetic + c.electric.powi(2) * electric) / lam2
+                + (c.anapole.powi(2) * anapole + c.charge_radius.powi(2) * charge_radius)
+                    / (lam2 * lam2)
+                + c.magnetic * c.charge_radius * int / (lam2 * self.lam))
+    }
+    /// Squared matrix element for Rayleigh scattering chi+γ -> chi+γ, summed
+    /// over the photon polarizations.
+    pub(super) fn elastic_amplitude_sqrd_photon(&self, t: f64) -> f64 {
+        let m2 = self.mx * self.mx;
+        16.0 * self.coeffs.rayleigh.powi(2) * t * t * (4.0 * m2 - t) / self.lam.powi(6)
+    }
+}
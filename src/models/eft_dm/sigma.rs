@@ -0,0 +1,97 @@
+use super::charged_fermions;
+use super::EftDm;
+use crate::utils::integration::*;
+use haliax_constants::electroweak::ALPHA_EM;
+use std::collections::BTreeMap;
+
+impl EftDm {
+    /// Compute the cross section for chi+chibar -> γ^* -> f+fbar for a SM
+    /// fermion with mass `mf`, `ncol` colors and electric charge `qf`. The
+    /// anapole and charge-radius operators act as contact interactions, and
+    /// the magnetic dipole interferes with the charge radius.
+    pub fn sigma_xx_ff(&self, cme: f64, mf: f64, ncol: f64, qf: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let mf2 = mf * mf;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_f = (1.0 - 4.0 * mf2 / s).sqrt();
+            let c = &self.coeffs;
+            let lam2 = self.lam * self.lam;
+
+            let dm = (c.magnetic.powi(2) * (1.0 + 8.0 * m2 / s) + c.electric.powi(2) * beta * beta)
+                / lam2
+                + 2.0
+                    * s
+                    * (c.anapole.powi(2) * beta * beta
+                        + c.charge_radius.powi(2) * (1.0 + 2.0 * m2 / s))
+                    / (lam2 * lam2)
+                + 12.0 * c.magnetic * c.charge_radius * self.mx / (lam2 * self.lam);
+            ALPHA_EM * ncol * qf * qf * (1.0 + 2.0 * mf2 / s) * beta_f * dm / (6.0 * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for chi+chibar -> γ + γ through t- and
+    /// u-channel DM exchange with dipole vertices and the Rayleigh contact
+    /// interaction.
+    pub fn sigma_xx_gg(&self, cme: f64) -> f64 {
+        if cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+
+            let mut sum = 0.0;
+            for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+                let t = m2 - s / 2.0 * (1.0 - beta * z);
+                let u = m2 - s / 2.0 * (1.0 + beta * z);
+                sum += wgt * self.amplitude_sqrd_xx_gg(s, t, u);
+            }
+            // Average over initial spins and include symmetry factor of 1/2
+            // for the identical final states.
+            sum / (256.0 * std::f64::consts::PI * s * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Squared matrix element for chi+chibar -> γ + γ summed over spins and
+    /// polarizations.
+    fn amplitude_sqrd_xx_gg(&self, s: f64, t: f64, u: f64) -> f64 {
+        let m2 = self.mx * self.mx;
+        let c = &self.coeffs;
+        let lam2 = self.lam * self.lam;
+        let dt = t - m2;
+        let du = u - m2;
+
+        let cd2 = c.magnetic.powi(2) + c.electric.powi(2);
+        let dipole = cd2
+            * cd2
+            * (-3.0 * m2.powi(4) + m2 * m2 * (t * t + u * u) + 8.0 * m2 * m2 * t * u
+                - 4.0 * m2 * t * u * (t + u)
+                + (t * u).powi(2))
+            / (lam2 * lam2 * dt * du);
+        let int = 2.0
+            * self.mx
+            * c.rayleigh
+            * (c.magnetic.powi(2) - c.electric.powi(2))
+            * (t * u - m2 * m2)
+            * s
+            * s
+            / (lam2 * lam2 * self.lam * dt * du);
+        let rayleigh = c.rayleigh.powi(2) * s * s * (s - 4.0 * m2) / lam2.powi(3);
+        16.0 * (dipole + int + rayleigh)
+    }
+    /// Compute the total annihilation cross section.
+    pub fn sigma_xx(&self, cme: f64) -> f64 {
+        self.sigma_xx_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_xx_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        for f in charged_fermions() {
+            channels.insert(f.name, self.sigma_xx_ff(cme, f.mass, f.ncol, f.charge));
+        }
+        channels.insert("aa", self.sigma_xx_gg(cme));
+        channels
+    }
+}
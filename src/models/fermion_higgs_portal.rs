@@ -0,0 +1,107 @@
+pub mod gamma;
+pub mod sigma;
+
+use super::{sm_fermions, FermionHiggsPortal};
+use crate::boltz::traits::{fermion_momentum_exchange_rate, DarkMatterModel, Statistics};
+use crate::observables::Annihilation;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+impl FermionHiggsPortal {
+    /// Construct a Dirac fermion with mass `mx`, cut-off scale `lam` and
+    /// mixing angle `theta`.
+    pub fn new(mx: f64, lam: f64, theta: f64) -> FermionHiggsPortal {
+        FermionHiggsPortal {
+            mx,
+            lam,
+            theta,
+            majorana: false,
+        }
+    }
+    /// Construct a Majorana fermion with mass `mx`, cut-off scale `lam` and
+    /// mixing angle `theta`.
+    pub fn new_majorana(mx: f64, lam: f64, theta: f64) -> FermionHiggsPortal {
+        FermionHiggsPortal {
+            mx,
+            lam,
+            theta,
+            majorana: true,
+        }
+    }
+    /// Factor multiplying squared matrix elements from the two Wick
+    /// contractions of the Majorana field.
+    pub(super) fn majorana_factor(&self) -> f64 {
+        if self.majorana {
+            4.0
+        } else {
+            1.0
+        }
+    }
+}
+
+impl DarkMatterModel for FermionHiggsPortal {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        2.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Fermion
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_xx(cme)
+    }
+    /// Compute the momentum exchange rate between the DM and SM.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        let fermions = sm_fermions().into_iter().filter(|f| f.mass > 0.0);
+        fermion_momentum_exchange_rate(self.mx, self.g(), x, fermions, |f, _, t| {
+            self.elastic_amplitude_sqrd(t, f.mass, f.ncol)
+        })
+    }
+    /// The Higgs resonance and the hh threshold.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![HIGGS_MASS / self.mx, 2.0 * HIGGS_MASS / self.mx]
+    }
+}
+
+impl Annihilation for FermionHiggsPortal {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_xx_channels(cme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The t- and u-channel DM exchange is suppressed by v^2 / Λ relative to
+    /// the contact interaction, so for large Λ chi+chibar -> h + h reduces to
+    /// the contact interaction and the s-channel Higgs.
+    #[test]
+    fn test_hh_contact_limit() {
+        let (mx, lam, theta) = (200.0, 1e8, 0.4_f64);
+        let (st, ct) = theta.sin_cos();
+        let mh2 = HIGGS_MASS * HIGGS_MASS;
+        for model in [
+            FermionHiggsPortal::new(mx, lam, theta),
+            FermionHiggsPortal::new_majorana(mx, lam, theta),
+        ]
+        .iter()
+        {
+            for &cme in [450.0, 600.0, 2000.0].iter() {
+                let s = cme * cme;
+                let beta = (1.0 - 4.0 * mx * mx / s).sqrt();
+                let beta_h = (1.0 - 4.0 * mh2 / s).sqrt();
+                let gam2 = mh2 * HIGGS_WIDTH.powi(2);
+                let amp = ((s + 2.0 * mh2).powi(2) + gam2) / ((s - mh2).powi(2) + gam2);
+                let bilinear = ct * ct * (s - 4.0 * mx * mx) + st * st * s;
+                let expected = model.majorana_factor() * bilinear * amp * beta_h
+                    / (64.0 * std::f64::consts::PI * lam * lam * s * beta);
+                let sigma = model.sigma_xx_hh(cme);
+                assert!((sigma - expected).abs() < 1e-4 * expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use super::FermionHiggsPortal;
+use haliax_constants::prelude::*;
+
+impl FermionHiggsPortal {
+    /// Squared matrix element for chi+f -> chi+f through t-channel Higgs
+    /// exchange, summed over the spins and colors of all particles.
+    pub(super) fn elastic_amplitude_sqrd(&self, t: f64, mf: f64, ncol: f64) -> f64 {
+        let (st, ct) = self.theta.sin_cos();
+        let m2 = self.mx * self.mx;
+        let dm = 2.0 * (ct * ct * (4.0 * m2 - t) - st * st * t);
+        let sm = 2.0 * (4.0 * mf * mf - t);
+        self.majorana_factor() * ncol * (mf / self.lam).powi(2) * dm * sm
+            / (t - HIGGS_MASS * HIGGS_MASS).powi(2)
+    }
+}
@@ -0,0 +1,125 @@
+use super::FermionHiggsPortal;
+use crate::models::sm_fermions;
+use crate::utils::integration::*;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+impl FermionHiggsPortal {
+    /// Spin-averaged square of the DM bilinear
+    ///     vbar(p2).(cosθ + iγ5 sinθ).u(p1)
+    /// given the squared center-of-mass energy `s`.
+    fn dm_bilinear_sqrd(&self, s: f64) -> f64 {
+        let (st, ct) = self.theta.sin_cos();
+        let m2 = self.mx * self.mx;
+        self.majorana_factor() * (ct * ct * (s - 4.0 * m2) + st * st * s) / 2.0
+    }
+    /// Compute the cross section for chi+chibar -> h^* -> X given the width
+    /// `width` of an off-shell Higgs with mass `cme` into X.
+    fn sigma_xx_schannel(&self, cme: f64, width: f64) -> f64 {
+        let s = cme * cme;
+        let mh2 = HIGGS_MASS * HIGGS_MASS;
+        let beta = (1.0 - 4.0 * self.mx * self.mx / s).sqrt();
+        let prop = (s - mh2).powi(2) + mh2 * HIGGS_WIDTH.powi(2);
+        self.dm_bilinear_sqrd(s) * (HIGGS_VEV / self.lam).powi(2) * width / (cme * beta * prop)
+    }
+    /// Compute the cross section for chi+chibar -> f+fbar.
+    pub fn sigma_xx_ff(&self, cme: f64, mf: f64, ncol: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * self.mx {
+            let beta_f = (1.0 - 4.0 * mf * mf / (cme * cme)).sqrt();
+            let width = ncol * mf * mf * cme * beta_f.powi(3)
+                / (8.0 * std::f64::consts::PI * HIGGS_VEV.powi(2));
+            self.sigma_xx_schannel(cme, width)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for chi+chibar -> V+V, where V is a massive
+    /// vector boson with mass `mv`. `sym` is 1 for W^+W^- and 1/2 for ZZ.
+    fn sigma_xx_vv(&self, cme: f64, mv: f64, sym: f64) -> f64 {
+        if cme > 2.0 * mv && cme > 2.0 * self.mx {
+            let r = mv * mv / (cme * cme);
+            let width = sym * cme.powi(3) * (1.0 - 4.0 * r).sqrt() * (1.0 - 4.0 * r + 12.0 * r * r)
+                / (16.0 * std::f64::consts::PI * HIGGS_VEV.powi(2));
+            self.sigma_xx_schannel(cme, width)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for chi+chibar -> W^+ + W^-.
+    pub fn sigma_xx_ww(&self, cme: f64) -> f64 {
+        self.sigma_xx_vv(cme, W_BOSON_MASS, 1.0)
+    }
+    /// Compute the cross section for chi+chibar -> Z + Z.
+    pub fn sigma_xx_zz(&self, cme: f64) -> f64 {
+        self.sigma_xx_vv(cme, Z_BOSON_MASS, 0.5)
+    }
+    /// Compute the cross section for chi+chibar -> h + h from the contact
+    /// interaction, the s-channel Higgs and the t- and u-channel DM exchange.
+    pub fn sigma_xx_hh(&self, cme: f64) -> f64 {
+        if cme > 2.0 * HIGGS_MASS && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let mh2 = HIGGS_MASS * HIGGS_MASS;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_h = (1.0 - 4.0 * mh2 / s).sqrt();
+            let e2 = s / 4.0;
+            // |p_i| |p_f|
+            let pp = e2 * beta * beta_h;
+
+            let mut sum = 0.0;
+            for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+                let t = m2 + mh2 - 2.0 * (e2 - pp * z);
+                let u = m2 + mh2 - 2.0 * (e2 + pp * z);
+                sum += wgt * self.amplitude_sqrd_xx_hh(s, t, u);
+            }
+            // Average over initial spins and include symmetry factor of 1/2
+            // for the identical final states.
+            beta_h * sum / (256.0 * std::f64::consts::PI * s * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Squared matrix element for chi+chibar -> h + h summed over spins.
+    /// Using the Dirac equation, the amplitude reduces to
+    ///     vbar(p2).(B (cosθ + iγ5 sinθ) + V k1).u(p1),
+    /// where k1 is the momentum of one of the Higgs bosons.
+    fn amplitude_sqrd_xx_hh(&self, s: f64, t: f64, u: f64) -> f64 {
+        let (st, ct) = self.theta.sin_cos();
+        let m2 = self.mx * self.mx;
+        let mh2 = HIGGS_MASS * HIGGS_MASS;
+        // Each DM vertex picks up a factor of 2 from the Majorana contractions
+        let vert = self.majorana_factor().sqrt();
+        let yuk2 = vert * (HIGGS_VEV / self.lam).powi(2);
+        let (pt, pu) = (1.0 / (t - m2), 1.0 / (u - m2));
+
+        // B = (1 + 3 mh^2 / (s - mh^2 + i mh Γh)) / Λ from the contact
+        // interaction and the s-channel Higgs, plus the scalar part of the
+        // t- and u-channel DM exchange.
+        let den = (s - mh2).powi(2) + mh2 * HIGGS_WIDTH.powi(2);
+        let re_b =
+            (1.0 + 3.0 * mh2 * (s - mh2) / den) / self.lam + 2.0 * yuk2 * self.mx * ct * (pt + pu);
+        let im_b = -3.0 * mh2 * HIGGS_MASS * HIGGS_WIDTH / (den * self.lam);
+        let v = -yuk2 * (pt - pu);
+
+        let scalar = 2.0 * (ct * ct * (s - 4.0 * m2) + st * st * s) * (re_b * re_b + im_b * im_b);
+        let vector = 2.0 * v * v * ((m2 + mh2 - t) * (m2 + mh2 - u) - mh2 * s);
+        let interference = 4.0 * self.mx * ct * re_b * v * (t - u);
+        vert * vert * (scalar + vector + interference)
+    }
+    /// Compute the total annihilation cross section.
+    pub fn sigma_xx(&self, cme: f64) -> f64 {
+        self.sigma_xx_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_xx_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        for f in sm_fermions().iter().filter(|f| f.mass > 0.0) {
+            channels.insert(f.name, self.sigma_xx_ff(cme, f.mass, f.ncol));
+        }
+        channels.insert("ZZ", self.sigma_xx_zz(cme));
+        channels.insert("WW", self.sigma_xx_ww(cme));
+        channels.insert("hh", self.sigma_xx_hh(cme));
+        channels
+    }
+}
@@ -0,0 +1,110 @@
+//! Cross sections are computed for SM fermion final states, through the
+//! s-channel Higgs (H0H0, A0A0, H^+H^-), Z (H0A0, H^+H^-), photon (H^+H^-)
+//! and W (H0H^±, A0H^±), and for the gauge and Higgs boson final states of
+//! all pairs (see `bosons`), including WW and ZZ with one off-shell vector.
+//! Coannihilations enter the `SimpleBoltzmann` solver; the `FullBoltzmann`
+//! solver only tracks H0. Call `tabulate` before solving the Boltzmann
+//! equation.
+
+pub mod bosons;
+pub mod gamma;
+pub mod sigma;
+
+use super::{sm_fermions, InertDoublet};
+use crate::boltz::traits::{fermion_momentum_exchange_rate, DarkMatterModel, Statistics};
+use crate::observables::Annihilation;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+/// Masses of the up- and down-type fermions and number of colors of the SM
+/// weak doublets, neglecting CKM mixing.
+pub(super) fn sm_doublets() -> [(&'static str, f64, f64, f64); 6] {
+    [
+        ("tb", TOP_QUARK_MASS, BOTTOM_QUARK_MASS, 3.0),
+        ("cs", CHARM_QUARK_MASS, STRANGE_QUARK_MASS, 3.0),
+        ("ud", UP_QUARK_MASS, DOWN_QUARK_MASS, 3.0),
+        ("nutau", 0.0, TAU_MASS, 1.0),
+        ("numu", 0.0, MUON_MASS, 1.0),
+        ("nue", 0.0, ELECTRON_MASS, 1.0),
+    ]
+}
+
+impl InertDoublet {
+    pub fn new(mh0: f64, dma: f64, dmc: f64, lam_l: f64) -> InertDoublet {
+        InertDoublet {
+            mh0,
+            dma,
+            dmc,
+            lam_l,
+            tables: None,
+        }
+    }
+    /// Mass of the pseudoscalar A0.
+    pub fn ma0(&self) -> f64 {
+        self.mh0 + self.dma
+    }
+    /// Mass of the charged scalars H^±.
+    pub fn mhc(&self) -> f64 {
+        self.mh0 + self.dmc
+    }
+    /// Coupling λ5 = (m_H0^2 - m_A0^2) / v^2.
+    pub fn lam_5(&self) -> f64 {
+        (self.mh0.powi(2) - self.ma0().powi(2)) / HIGGS_VEV.powi(2)
+    }
+    /// Higgs-portal coupling of A0, λ_A = (λ3 + λ4 - λ5) / 2.
+    pub fn lam_a(&self) -> f64 {
+        self.lam_l - self.lam_5()
+    }
+    /// Coupling λ3 = 2 (m_H±^2 - μ2^2) / v^2, which sets the hH^+H^-
+    /// coupling.
+    pub fn lam_3(&self) -> f64 {
+        let mu22 = self.mh0.powi(2) - self.lam_l * HIGGS_VEV.powi(2);
+        2.0 * (self.mhc().powi(2) - mu22) / HIGGS_VEV.powi(2)
+    }
+}
+
+impl DarkMatterModel for InertDoublet {
+    fn mass(&self) -> f64 {
+        self.mh0
+    }
+    fn g(&self) -> f64 {
+        1.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Boson
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_coann(0, 0, cme)
+    }
+    /// Compute the momentum exchange rate between the DM and SM.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        let fermions = sm_fermions().into_iter().filter(|f| f.mass > 0.0);
+        fermion_momentum_exchange_rate(self.mh0, self.g(), x, fermions, |f, _, t| {
+            self.elastic_amplitude_sqrd(t, f.mass, f.ncol)
+        })
+    }
+    /// The Higgs, Z and W resonances.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![
+            HIGGS_MASS / self.mh0,
+            Z_BOSON_MASS / self.mh0,
+            W_BOSON_MASS / self.mh0,
+        ]
+    }
+    /// The pseudoscalar A0 and the charged scalars H^±.
+    fn partners(&self) -> Vec<(f64, f64)> {
+        vec![(self.ma0(), 1.0), (self.mhc(), 2.0)]
+    }
+    /// Cross sections among H0 (0), A0 (1) and H^± (2). Cross sections
+    /// involving H^± are averaged over the charges.
+    fn sigma_coann(&self, i: usize, j: usize, cme: f64) -> f64 {
+        self.sigma_fermions(i, j, cme) + self.sigma_bosons_tabulated(i, j, cme)
+    }
+}
+
+impl Annihilation for InertDoublet {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_h0h0_channels(cme)
+    }
+}
@@ -0,0 +1,885 @@
+//! Cross sections for annihilations of the inert scalars into pairs of gauge
+//! and Higgs bosons. The amplitudes into two vectors are built as tensors
+//! M^{μν} from the momenta in the center-of-mass frame and contracted with
+//! the polarization sums of the vectors: -g + k k / m^2 for the W and Z, and
+//! the two physical polarizations for the photon, using the momentum of the
+//! other vector as reference. The WW and ZZ final states include one
+//! off-shell vector decaying into massless fermions, which dominates for
+//! masses below the thresholds. The other final states are taken on-shell.
+//!
+//! The solvers evaluate the cross sections many times per step, so
+//! `InertDoublet::tabulate` interpolates them in the center-of-mass energy.
+
+use super::sigma::{cm_momentum, g_weak, propagator};
+use super::InertDoublet;
+use crate::utils::integration::*;
+use crate::utils::interpolation::LogSpline;
+use haliax_constants::prelude::*;
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+/// Number of log-spaced energies at which each channel is tabulated.
+const TABLE_SIZE: usize = 200;
+/// Range of tabulated energies above threshold relative to the threshold.
+/// Beyond the range the cross sections are computed directly.
+const TABLE_EMIN: f64 = 1e-6;
+const TABLE_EMAX: f64 = 50.0;
+
+/// Pairs of species (0 = H0, 1 = A0, 2 = H^±) in the order of the tables.
+const PAIRS: [(usize, usize); 6] = [(0, 0), (1, 1), (2, 2), (0, 1), (0, 2), (1, 2)];
+
+type FourVector = [f64; 4];
+type Tensor = [[f64; 4]; 4];
+
+/// Cross section of a single channel as a function of the center-of-mass
+/// energy.
+type Channel = fn(&InertDoublet, f64) -> f64;
+
+/// cos(θ_W) from sin^2(θ_W). The gauge cancellations at high energies rely
+/// on the tree-level relation between the two.
+fn cos_weak() -> f64 {
+    (1.0 - SIN_THETA_WEAK_SQRD).sqrt()
+}
+
+/// Diagonal of the metric with signature (+, -, -, -).
+const METRIC: FourVector = [1.0, -1.0, -1.0, -1.0];
+
+fn dot(a: &FourVector, b: &FourVector) -> f64 {
+    a[0] * b[0] - a[1] * b[1] - a[2] * b[2] - a[3] * b[3]
+}
+
+/// Linear combination c1 a + c2 b.
+fn lin(c1: f64, a: &FourVector, c2: f64, b: &FourVector) -> FourVector {
+    [
+        c1 * a[0] + c2 * b[0],
+        c1 * a[1] + c2 * b[1],
+        c1 * a[2] + c2 * b[2],
+        c1 * a[3] + c2 * b[3],
+    ]
+}
+
+/// Invariant mass squared of a - b.
+fn diff_sqrd(a: &FourVector, b: &FourVector) -> f64 {
+    let d = lin(1.0, a, -1.0, b);
+    dot(&d, &d)
+}
+
+fn lower(a: &FourVector) -> FourVector {
+    [a[0], -a[1], -a[2], -a[3]]
+}
+
+fn outer(a: &FourVector, b: &FourVector) -> Tensor {
+    let mut t = [[0.0; 4]; 4];
+    for (ti, ai) in t.iter_mut().zip(a.iter()) {
+        for (tij, bj) in ti.iter_mut().zip(b.iter()) {
+            *tij = ai * bj;
+        }
+    }
+    t
+}
+
+/// Add c u to the tensor t.
+fn add_scaled(t: &mut Tensor, c: f64, u: &Tensor) {
+    for (ti, ui) in t.iter_mut().zip(u.iter()) {
+        for (tij, uij) in ti.iter_mut().zip(ui.iter()) {
+            *tij += c * uij;
+        }
+    }
+}
+
+/// Sum over polarizations of ε_μ ε*_ρ for a vector with momentum `k` and
+/// mass `mass`. For a photon only the physical polarizations are kept, using
+/// `n` as the reference vector.
+fn polarization_sum(k: &FourVector, mass: f64, n: &FourVector) -> Tensor {
+    let kl = lower(k);
+    let mut pol = [[0.0; 4]; 4];
+    for (i, &g) in METRIC.iter().enumerate() {
+        pol[i][i] = -g;
+    }
+    if mass > 0.0 {
+        add_scaled(&mut pol, 1.0 / (mass * mass), &outer(&kl, &kl));
+    } else {
+        let nl = lower(n);
+        let kn = dot(k, n);
+        add_scaled(&mut pol, 1.0 / kn, &outer(&kl, &nl));
+        add_scaled(&mut pol, 1.0 / kn, &outer(&nl, &kl));
+        add_scaled(&mut pol, -dot(n, n) / (kn * kn), &outer(&kl, &kl));
+    }
+    pol
+}
+
+/// Sum of M^{μν} M^{ρσ} P1_{μρ} P2_{νσ} for a real tensor M.
+fn contract(m: &Tensor, pol1: &Tensor, pol2: &Tensor) -> f64 {
+    // a^μ_σ = M^{μν} P2_{νσ}
+    let mut a = [[0.0; 4]; 4];
+    for (ai, mi) in a.iter_mut().zip(m.iter()) {
+        for (nu, mij) in mi.iter().enumerate() {
+            for (aik, pjk) in ai.iter_mut().zip(pol2[nu].iter()) {
+                *aik += mij * pjk;
+            }
+        }
+    }
+    let mut sum = 0.0;
+    for (ai, p1i) in a.iter().zip(pol1.iter()) {
+        for (mr, p1ir) in m.iter().zip(p1i.iter()) {
+            let b: f64 = ai.iter().zip(mr.iter()).map(|(x, y)| x * y).sum();
+            sum += p1ir * b;
+        }
+    }
+    sum
+}
+
+/// Momenta for S1(p1) + S2(p2) -> X1(k1) + X2(k2) in the center-of-mass
+/// frame, with cos(θ) = `z` between p1 and k1.
+struct Kinematics {
+    p1: FourVector,
+    p2: FourVector,
+    k1: FourVector,
+    k2: FourVector,
+}
+
+impl Kinematics {
+    fn new(cme: f64, m1: f64, m2: f64, n1: f64, n2: f64, z: f64) -> Kinematics {
+        let s = cme * cme;
+        let pi = cm_momentum(s, m1, m2);
+        let pf = cm_momentum(s, n1, n2);
+        let e1 = (s + m1 * m1 - m2 * m2) / (2.0 * cme);
+        let f1 = (s + n1 * n1 - n2 * n2) / (2.0 * cme);
+        let sz = (1.0 - z * z).max(0.0).sqrt();
+        Kinematics {
+            p1: [e1, 0.0, 0.0, pi],
+            p2: [cme - e1, 0.0, 0.0, -pi],
+            k1: [f1, pf * sz, 0.0, pf * z],
+            k2: [cme - f1, -pf * sz, 0.0, -pf * z],
+        }
+    }
+    fn total(&self) -> FourVector {
+        lin(1.0, &self.p1, 1.0, &self.p2)
+    }
+    fn s(&self) -> f64 {
+        let q = self.total();
+        dot(&q, &q)
+    }
+    /// The scalar current p1 - p2 contracted with the numerator of the
+    /// propagator of an s-channel vector with mass `mass`.
+    fn current(&self, mass: f64) -> FourVector {
+        let q = self.total();
+        let j = lin(1.0, &self.p1, -1.0, &self.p2);
+        lin(1.0, &j, -dot(&q, &j) / (mass * mass), &q)
+    }
+    /// The current `j` of an s-channel vector contracted with its
+    /// triple-gauge vertex with the final-state vectors, with the indices of
+    /// k1 and k2 raised:
+    ///     j^μ (Q + k1)^ν + g^{μν} j·(k2 - k1) - (Q + k2)^μ j^ν
+    fn triple_gauge(&self, j: &FourVector) -> Tensor {
+        let q = self.total();
+        let mut t = outer(j, &lin(1.0, &q, 1.0, &self.k1));
+        add_scaled(&mut t, -1.0, &outer(&lin(1.0, &q, 1.0, &self.k2), j));
+        let jk = dot(j, &lin(1.0, &self.k2, -1.0, &self.k1));
+        for (i, &g) in METRIC.iter().enumerate() {
+            t[i][i] += g * jk;
+        }
+        t
+    }
+}
+
+/// Amplitude M^{μν} for S1 + S2 -> V1 + V2 with the indices of V1 and V2
+/// raised, split into real and imaginary parts.
+#[derive(Default)]
+struct VectorPairAmplitude {
+    re: Tensor,
+    im: Tensor,
+}
+
+impl VectorPairAmplitude {
+    /// Add c g^{μν} with complex c.
+    fn add_metric(&mut self, c: (f64, f64)) {
+        for (i, &g) in METRIC.iter().enumerate() {
+            self.re[i][i] += c.0 * g;
+            self.im[i][i] += c.1 * g;
+        }
+    }
+    /// Add c a^μ b^ν with real c.
+    fn add_outer(&mut self, c: f64, a: &FourVector, b: &FourVector) {
+        add_scaled(&mut self.re, c, &outer(a, b));
+    }
+    /// Add c t^{μν} with complex c.
+    fn add_tensor(&mut self, c: (f64, f64), t: &Tensor) {
+        add_scaled(&mut self.re, c.0, t);
+        add_scaled(&mut self.im, c.1, t);
+    }
+    /// Squared amplitude summed over the polarizations of vectors with
+    /// masses `n1` and `n2`.
+    fn squared(&self, kin: &Kinematics, n1: f64, n2: f64) -> f64 {
+        let pol1 = polarization_sum(&kin.k1, n1, &kin.k2);
+        let pol2 = polarization_sum(&kin.k2, n2, &kin.k1);
+        contract(&self.re, &pol1, &pol2) + contract(&self.im, &pol1, &pol2)
+    }
+}
+
+/// Integrate the squared amplitude `msqrd` over the scattering angle to get
+/// the cross section for S1 + S2 -> X1 + X2, where `sym` is 1/2 for
+/// identical final states.
+fn cross_section<F>(cme: f64, m1: f64, m2: f64, n1: f64, n2: f64, sym: f64, msqrd: F) -> f64
+where
+    F: Fn(&Kinematics) -> f64,
+{
+    if cme <= m1 + m2 || cme <= n1 + n2 {
+        return 0.0;
+    }
+    let s = cme * cme;
+    let pi = cm_momentum(s, m1, m2);
+    let pf = cm_momentum(s, n1, n2);
+    let sum: f64 = (*GAUSS_LEG_NS)
+        .iter()
+        .zip((*GAUSS_LEG_WS).iter())
+        .map(|(z, wgt)| wgt * msqrd(&Kinematics::new(cme, m1, m2, n1, n2, *z)))
+        .sum();
+    sym * pf * sum / (32.0 * PI * s * pi)
+}
+
+/// Cross section into V + V including the final states where one of the
+/// vectors, with mass M and width Γ, is off-shell, given the cross section
+/// `sigma(n1, n2)` into vectors with masses n1 and n2. The heavier vector is
+/// taken on-shell and the lighter one, with invariant mass q < M, is
+/// weighted by the spectral function of V^* -> f + fbar into massless
+/// fermions:
+///     σ = 2 ∫_0^{M^2} dq^2 ρ(q^2) σ(M, q),
+///     ρ(q^2) = q^2 Γ / (π M |q^2 - M^2 + i M Γ|^2).
+/// Well above threshold this reduces to the on-shell cross section, and it
+/// varies smoothly across the threshold, unlike subtracting the on-shell
+/// cross section from the integral over all q.
+fn off_shell<F>(cme: f64, mass: f64, width: f64, sigma: F) -> f64
+where
+    F: Fn(f64, f64) -> f64,
+{
+    if cme <= mass {
+        return 0.0;
+    }
+    let m2 = mass * mass;
+    let qmax2 = (cme - mass).powi(2).min(m2);
+    // Map q^2 = M^2 + M Γ tan(θ), which flattens the Breit-Wigner.
+    let th_min = (-mass / width).atan();
+    let th_max = ((qmax2 - m2) / (mass * width)).atan();
+    let sum: f64 = (*GAUSS_LEG_NS)
+        .iter()
+        .zip((*GAUSS_LEG_WS).iter())
+        .map(|(z, wgt)| {
+            let th = th_min + (th_max - th_min) * (z + 1.0) / 2.0;
+            let q2 = m2 + mass * width * th.tan();
+            wgt * q2 / m2 * sigma(mass, q2.sqrt())
+        })
+        .sum();
+    sum * (th_max - th_min) / PI
+}
+
+/// Amplitude for S1 + S2 -> V1 + V2 through the S1S2V1V2 contact interaction
+/// 2 `a` g^{μν}, the s-channel Higgs with hS1S2 coupling `c v` and hV1V2
+/// coupling `ghvv`, and the t- and u-channel exchange of a scalar with mass
+/// `mex` whose couplings to V1 and V2 multiply to `a`.
+fn scalar_exchange_amplitude(
+    kin: &Kinematics,
+    a: f64,
+    mex: f64,
+    c: f64,
+    ghvv: f64,
+) -> VectorPairAmplitude {
+    let (re, im) = propagator(kin.s(), HIGGS_MASS, HIGGS_WIDTH);
+    let h = c * HIGGS_VEV * ghvv;
+    let t = diff_sqrd(&kin.p1, &kin.k1);
+    let u = diff_sqrd(&kin.p1, &kin.k2);
+    let mut amp = VectorPairAmplitude::default();
+    amp.add_metric((2.0 * a + h * re, h * im));
+    amp.add_outer(
+        a / (t - mex * mex),
+        &lin(2.0, &kin.p1, -1.0, &kin.k1),
+        &lin(2.0, &kin.p2, -1.0, &kin.k2),
+    );
+    amp.add_outer(
+        a / (u - mex * mex),
+        &lin(2.0, &kin.p2, -1.0, &kin.k1),
+        &lin(2.0, &kin.p1, -1.0, &kin.k2),
+    );
+    amp
+}
+
+/// Amplitude for S(p1) + H^+(p2) -> W^+(k1) + V(k2), where S is H0 or A0 and
+/// V is a Z or photon, through the SH^+W^-V contact interaction `contact`,
+/// the t-channel H^+ with couplings multiplying to `tcoup`, the u-channel
+/// exchange of the other neutral scalar with mass `mn` and couplings
+/// multiplying to `ucoup`, and the s-channel W with WWV coupling `gv`.
+fn shc_wv_amplitude(
+    kin: &Kinematics,
+    mn: f64,
+    gv: f64,
+    contact: f64,
+    tcoup: f64,
+    ucoup: f64,
+) -> VectorPairAmplitude {
+    let g = g_weak();
+    let mc2 = dot(&kin.p2, &kin.p2);
+    let t = diff_sqrd(&kin.p2, &kin.k2);
+    let u = diff_sqrd(&kin.p1, &kin.k2);
+    let (re, im) = propagator(kin.s(), W_BOSON_MASS, W_BOSON_WIDTH);
+    let c = g * gv / 2.0;
+    let mut amp = VectorPairAmplitude::default();
+    amp.add_metric((contact, 0.0));
+    amp.add_outer(
+        tcoup / (t - mc2),
+        &lin(2.0, &kin.p1, -1.0, &kin.k1),
+        &lin(2.0, &kin.p2, -1.0, &kin.k2),
+    );
+    if ucoup != 0.0 {
+        amp.add_outer(
+            ucoup / (u - mn * mn),
+            &lin(2.0, &kin.p2, -1.0, &kin.k1),
+            &lin(2.0, &kin.p1, -1.0, &kin.k2),
+        );
+    }
+    let tg = kin.triple_gauge(&kin.current(W_BOSON_MASS));
+    amp.add_tensor((c * re, c * im), &tg);
+    amp
+}
+
+/// Squared amplitude for S1(p1) + S2(p2) -> V(k1) + h(k2), summed over the
+/// polarizations of V, through the s-channel V with width `width` and the
+/// t- and u-channel exchange of S1 and S2. The S1S2V vertex is
+/// (gs / 2)(p1 - p2), the hVV coupling is `ghvv` and the hS1S1 and hS2S2
+/// couplings are `c1 v` and `c2 v`.
+fn vh_msqrd(kin: &Kinematics, width: f64, gs: f64, ghvv: f64, c1: f64, c2: f64) -> f64 {
+    let mv = dot(&kin.k1, &kin.k1).sqrt();
+    let (re, im) = propagator(kin.s(), mv, width);
+    let j = kin.current(mv);
+    let t = diff_sqrd(&kin.p1, &kin.k2);
+    let u = diff_sqrd(&kin.p2, &kin.k2);
+    let ct = -c1 * HIGGS_VEV / (t - dot(&kin.p1, &kin.p1));
+    let cu = c2 * HIGGS_VEV / (u - dot(&kin.p2, &kin.p2));
+    let amp_re = lin(
+        1.0,
+        &lin(ghvv * re, &j, ct, &lin(2.0, &kin.p2, -1.0, &kin.k1)),
+        cu,
+        &lin(2.0, &kin.p1, -1.0, &kin.k1),
+    );
+    let amp_im = lin(ghvv * im, &j, 0.0, &j);
+    let pol = polarization_sum(&kin.k1, mv, &kin.k2);
+    let quad = |a: &FourVector| -> f64 {
+        a.iter()
+            .zip(pol.iter())
+            .map(|(ai, pi)| {
+                ai * a
+                    .iter()
+                    .zip(pi.iter())
+                    .map(|(aj, pij)| aj * pij)
+                    .sum::<f64>()
+            })
+            .sum()
+    };
+    (gs / 2.0).powi(2) * (quad(&amp_re) + quad(&amp_im))
+}
+
+/// Cross section for S1 + S2 -> V1 + V2 with masses `n1` and `n2` given the
+/// amplitude `amp`.
+fn sigma_vv<F>(cme: f64, m1: f64, m2: f64, n1: f64, n2: f64, sym: f64, amp: F) -> f64
+where
+    F: Fn(&Kinematics) -> VectorPairAmplitude,
+{
+    cross_section(cme, m1, m2, n1, n2, sym, |kin| {
+        amp(kin).squared(kin, n1, n2)
+    })
+}
+
+/// Cross section for S1 + S2 -> V + V including one off-shell V with mass
+/// `mass` and width `width`, given the amplitude `amp`.
+fn sigma_vv_off_shell<F>(cme: f64, m1: f64, m2: f64, mass: f64, width: f64, sym: f64, amp: F) -> f64
+where
+    F: Fn(&Kinematics) -> VectorPairAmplitude,
+{
+    off_shell(cme, mass, width, |n1, n2| {
+        sigma_vv(cme, m1, m2, n1, n2, sym, &amp)
+    })
+}
+
+/// Cross section for S + S' -> h + h, where S and S' have mass `ms`, the
+/// hSS' and hhSS' couplings are `c v` and `c` and S is exchanged in the t-
+/// and u-channels.
+fn sigma_ss_hh(cme: f64, ms: f64, c: f64) -> f64 {
+    let mh2 = HIGGS_MASS * HIGGS_MASS;
+    let ms2 = ms * ms;
+    let v2 = HIGGS_VEV * HIGGS_VEV;
+    cross_section(cme, ms, ms, HIGGS_MASS, HIGGS_MASS, 0.5, |kin| {
+        let (re, im) = propagator(kin.s(), HIGGS_MASS, HIGGS_WIDTH);
+        let t = diff_sqrd(&kin.p1, &kin.k1);
+        let u = diff_sqrd(&kin.p1, &kin.k2);
+        let amp_re = -c * (1.0 + 3.0 * mh2 * re) - c * c * v2 * (1.0 / (t - ms2) + 1.0 / (u - ms2));
+        let amp_im = -3.0 * c * mh2 * im;
+        amp_re * amp_re + amp_im * amp_im
+    })
+}
+
+/// Cross section of a single channel tabulated in the center-of-mass energy
+/// above the energy at which it opens.
+struct ChannelTable {
+    sigma: Channel,
+    weight: f64,
+    threshold: f64,
+    spline: Option<LogSpline>,
+}
+
+impl ChannelTable {
+    fn new(model: &InertDoublet, sigma: Channel, weight: f64, threshold: f64) -> ChannelTable {
+        let (lemin, lemax) = ((TABLE_EMIN * threshold).ln(), (TABLE_EMAX * threshold).ln());
+        let mut es: Vec<f64> = (0..TABLE_SIZE)
+            .map(|i| (lemin + (lemax - lemin) * i as f64 / (TABLE_SIZE - 1) as f64).exp())
+            .collect();
+        // Resolve the s-channel resonances and the on-shell WW and ZZ
+        // thresholds, which vary on scales much smaller than the spacing of
+        // the grid.
+        for &(mass, width) in [
+            (HIGGS_MASS, HIGGS_WIDTH),
+            (Z_BOSON_MASS, Z_BOSON_WIDTH),
+            (W_BOSON_MASS, W_BOSON_WIDTH),
+            (2.0 * Z_BOSON_MASS, Z_BOSON_WIDTH),
+            (2.0 * W_BOSON_MASS, W_BOSON_WIDTH),
+        ]
+        .iter()
+        {
+            for k in -20i32..=20 {
+                let e = mass - threshold + width * (k * k.abs()) as f64 / 4.0;
+                if e > es[0] && e < es[TABLE_SIZE - 1] {
+                    es.push(e);
+                }
+            }
+        }
+        es.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        es.dedup();
+        let ss: Vec<f64> = es.iter().map(|e| sigma(model, threshold + e)).collect();
+        ChannelTable {
+            sigma,
+            weight,
+            threshold,
+            spline: LogSpline::new(&es, &ss).ok(),
+        }
+    }
+    fn eval(&self, model: &InertDoublet, cme: f64) -> f64 {
+        let e = cme - self.threshold;
+        if e <= 0.0 {
+            0.0
+        } else if e > TABLE_EMAX * self.threshold {
+            self.weight * (self.sigma)(model, cme)
+        } else {
+            self.spline
+                .as_ref()
+                .map_or(0.0, |sp| self.weight * sp.eval(e))
+        }
+    }
+}
+
+/// Cross sections into boson final states of each pair of inert scalars,
+/// tabulated for fixed masses and couplings.
+pub(crate) struct BosonTables {
+    /// Parameters (m_H0, Δm_A0, Δm_H±, λ_L) of the tables.
+    params: [f64; 4],
+    /// Channels of each pair in `PAIRS`.
+    pairs: Vec<Vec<ChannelTable>>,
+}
+
+impl InertDoublet {
+    /// Compute the cross section for H0+H0 -> W^+ + W^-, including one
+    /// off-shell W.
+    pub fn sigma_h0h0_ww(&self, cme: f64) -> f64 {
+        let g = g_weak();
+        let (m, mex, c) = (self.mh0, self.mhc(), 2.0 * self.lam_l);
+        sigma_vv_off_shell(cme, m, m, W_BOSON_MASS, W_BOSON_WIDTH, 1.0, |kin| {
+            scalar_exchange_amplitude(kin, g * g / 4.0, mex, c, g * W_BOSON_MASS)
+        })
+    }
+    /// Compute the cross section for H0+H0 -> Z + Z, including one off-shell
+    /// Z.
+    pub fn sigma_h0h0_zz(&self, cme: f64) -> f64 {
+        let gz = g_weak() / cos_weak();
+        let (m, mex, c) = (self.mh0, self.ma0(), 2.0 * self.lam_l);
+        sigma_vv_off_shell(cme, m, m, Z_BOSON_MASS, Z_BOSON_WIDTH, 0.5, |kin| {
+            scalar_exchange_amplitude(kin, gz * gz / 4.0, mex, c, gz * Z_BOSON_MASS)
+        })
+    }
+    /// Compute the cross section for H0+H0 -> h + h.
+    pub fn sigma_h0h0_hh(&self, cme: f64) -> f64 {
+        sigma_ss_hh(cme, self.mh0, 2.0 * self.lam_l)
+    }
+    /// Compute the cross section for A0+A0 -> W^+ + W^-, including one
+    /// off-shell W.
+    pub fn sigma_a0a0_ww(&self, cme: f64) -> f64 {
+        let g = g_weak();
+        let (m, mex, c) = (self.ma0(), self.mhc(), 2.0 * self.lam_a());
+        sigma_vv_off_shell(cme, m, m, W_BOSON_MASS, W_BOSON_WIDTH, 1.0, |kin| {
+            scalar_exchange_amplitude(kin, g * g / 4.0, mex, c, g * W_BOSON_MASS)
+        })
+    }
+    /// Compute the cross section for A0+A0 -> Z + Z, including one off-shell
+    /// Z.
+    pub fn sigma_a0a0_zz(&self, cme: f64) -> f64 {
+        let gz = g_weak() / cos_weak();
+        let (m, mex, c) = (self.ma0(), self.mh0, 2.0 * self.lam_a());
+        sigma_vv_off_shell(cme, m, m, Z_BOSON_MASS, Z_BOSON_WIDTH, 0.5, |kin| {
+            scalar_exchange_amplitude(kin, gz * gz / 4.0, mex, c, gz * Z_BOSON_MASS)
+        })
+    }
+    /// Compute the cross section for A0+A0 -> h + h.
+    pub fn sigma_a0a0_hh(&self, cme: f64) -> f64 {
+        sigma_ss_hh(cme, self.ma0(), 2.0 * self.lam_a())
+    }
+    /// Compute the cross section for H^+ + H^- -> W^+ + W^-, including one
+    /// off-shell W.
+    pub fn sigma_hphm_ww(&self, cme: f64) -> f64 {
+        let g = g_weak();
+        let g2 = g * g;
+        let e2 = 4.0 * PI * ALPHA_EM;
+        let cos_2w = 1.0 - 2.0 * SIN_THETA_WEAK_SQRD;
+        let (mc, mh02, ma02) = (self.mhc(), self.mh0.powi(2), self.ma0().powi(2));
+        let c = self.lam_3();
+        sigma_vv_off_shell(cme, mc, mc, W_BOSON_MASS, W_BOSON_WIDTH, 1.0, |kin| {
+            let s = kin.s();
+            let (hre, him) = propagator(s, HIGGS_MASS, HIGGS_WIDTH);
+            let (zre, zim) = propagator(s, Z_BOSON_MASS, Z_BOSON_WIDTH);
+            let h = c * HIGGS_VEV * g * W_BOSON_MASS;
+            let t = diff_sqrd(&kin.p1, &kin.k1);
+            let mut amp = VectorPairAmplitude::default();
+            amp.add_metric((g2 / 2.0 + h * hre, h * him));
+            amp.add_outer(
+                g2 / 4.0 * (1.0 / (t - mh02) + 1.0 / (t - ma02)),
+                &lin(2.0, &kin.p1, -1.0, &kin.k1),
+                &lin(2.0, &kin.p2, -1.0, &kin.k2),
+            );
+            let cz = g2 * cos_2w / 2.0;
+            let tg = kin.triple_gauge(&kin.current(Z_BOSON_MASS));
+            amp.add_tensor((e2 / s + cz * zre, cz * zim), &tg);
+            amp
+        })
+    }
+    /// Compute the cross section for H^+ + H^- -> Z + Z, including one
+    /// off-shell Z.
+    pub fn sigma_hphm_zz(&self, cme: f64) -> f64 {
+        let gz = g_weak() / cos_weak();
+        let a = (gz * (1.0 - 2.0 * SIN_THETA_WEAK_SQRD) / 2.0).powi(2);
+        let (mc, c) = (self.mhc(), self.lam_3());
+        sigma_vv_off_shell(cme, mc, mc, Z_BOSON_MASS, Z_BOSON_WIDTH, 0.5, |kin| {
+            scalar_exchange_amplitude(kin, a, mc, c, gz * Z_BOSON_MASS)
+        })
+    }
+    /// Compute the cross section for H^+ + H^- -> γ + γ.
+    pub fn sigma_hphm_aa(&self, cme: f64) -> f64 {
+        let e2 = 4.0 * PI * ALPHA_EM;
+        let mc = self.mhc();
+        sigma_vv(cme, mc, mc, 0.0, 0.0, 0.5, |kin| {
+            scalar_exchange_amplitude(kin, e2, mc, 0.0, 0.0)
+        })
+    }
+    /// Compute the cross section for H^+ + H^- -> Z + γ.
+    pub fn sigma_hphm_za(&self, cme: f64) -> f64 {
+        let e = (4.0 * PI * ALPHA_EM).sqrt();
+        let gz = g_weak() / cos_weak();
+        let a = e * gz * (1.0 - 2.0 * SIN_THETA_WEAK_SQRD) / 2.0;
+        let mc = self.mhc();
+        sigma_vv(cme, mc, mc, Z_BOSON_MASS, 0.0, 1.0, |kin| {
+            scalar_exchange_amplitude(kin, a, mc, 0.0, 0.0)
+        })
+    }
+    /// Compute the cross section for H^+ + H^- -> Z + h.
+    pub fn sigma_hphm_zh(&self, cme: f64) -> f64 {
+        let gz = g_weak() / cos_weak();
+        let gs = -gz * (1.0 - 2.0 * SIN_THETA_WEAK_SQRD);
+        let (mc, c) = (self.mhc(), self.lam_3());
+        cross_section(cme, mc, mc, Z_BOSON_MASS, HIGGS_MASS, 1.0, |kin| {
+            vh_msqrd(kin, Z_BOSON_WIDTH, gs, gz * Z_BOSON_MASS, c, c)
+        })
+    }
+    /// Compute the cross section for H^+ + H^- -> h + h.
+    pub fn sigma_hphm_hh(&self, cme: f64) -> f64 {
+        sigma_ss_hh(cme, self.mhc(), self.lam_3())
+    }
+    /// Compute the cross section for H^+ + H^+ -> W^+ + W^+ through the
+    /// t- and u-channel H0 and A0, including one off-shell W. The amplitude
+    /// is proportional to λ5.
+    pub fn sigma_hphp_ww(&self, cme: f64) -> f64 {
+        let g2 = g_weak().powi(2);
+        let (mc, mh02, ma02) = (self.mhc(), self.mh0.powi(2), self.ma0().powi(2));
+        sigma_vv_off_shell(cme, mc, mc, W_BOSON_MASS, W_BOSON_WIDTH, 0.5, |kin| {
+            let t = diff_sqrd(&kin.p1, &kin.k1);
+            let u = diff_sqrd(&kin.p1, &kin.k2);
+            let mut amp = VectorPairAmplitude::default();
+            amp.add_outer(
+                g2 / 4.0 * (1.0 / (t - ma02) - 1.0 / (t - mh02)),
+                &lin(2.0, &kin.p1, -1.0, &kin.k1),
+                &lin(2.0, &kin.p2, -1.0, &kin.k2),
+            );
+            amp.add_outer(
+                g2 / 4.0 * (1.0 / (u - ma02) - 1.0 / (u - mh02)),
+                &lin(2.0, &kin.p2, -1.0, &kin.k1),
+                &lin(2.0, &kin.p1, -1.0, &kin.k2),
+            );
+            amp
+        })
+    }
+    /// Compute the cross section for H0+A0 -> W^+ + W^-, including one
+    /// off-shell W.
+    pub fn sigma_h0a0_ww(&self, cme: f64) -> f64 {
+        let g2 = g_weak().powi(2);
+        let mc2 = self.mhc().powi(2);
+        sigma_vv_off_shell(
+            cme,
+            self.mh0,
+            self.ma0(),
+            W_BOSON_MASS,
+            W_BOSON_WIDTH,
+            1.0,
+            |kin| {
+                let (re, im) = propagator(kin.s(), Z_BOSON_MASS, Z_BOSON_WIDTH);
+                let t = diff_sqrd(&kin.p1, &kin.k1);
+                let u = diff_sqrd(&kin.p1, &kin.k2);
+                let mut amp = VectorPairAmplitude::default();
+                amp.add_outer(
+                    g2 / 4.0 / (t - mc2),
+                    &lin(2.0, &kin.p1, -1.0, &kin.k1),
+                    &lin(2.0, &kin.p2, -1.0, &kin.k2),
+                );
+                amp.add_outer(
+                    -g2 / 4.0 / (u - mc2),
+                    &lin(2.0, &kin.p2, -1.0, &kin.k1),
+                    &lin(2.0, &kin.p1, -1.0, &kin.k2),
+                );
+                let tg = kin.triple_gauge(&kin.current(Z_BOSON_MASS));
+                amp.add_tensor((g2 / 2.0 * re, g2 / 2.0 * im), &tg);
+                amp
+            },
+        )
+    }
+    /// Compute the cross section for H0+A0 -> Z + h.
+    pub fn sigma_h0a0_zh(&self, cme: f64) -> f64 {
+        let gz = g_weak() / cos_weak();
+        let (c1, c2) = (2.0 * self.lam_l, 2.0 * self.lam_a());
+        cross_section(
+            cme,
+            self.mh0,
+            self.ma0(),
+            Z_BOSON_MASS,
+            HIGGS_MASS,
+            1.0,
+            |kin| vh_msqrd(kin, Z_BOSON_WIDTH, gz, gz * Z_BOSON_MASS, c1, c2),
+        )
+    }
+    /// Compute the cross section for S + H^+ -> W^+ + Z, where S is H0 or A0
+    /// with mass `ms` and `mn` is the mass of the other neutral scalar.
+    fn sigma_shc_wz(&self, cme: f64, ms: f64, mn: f64) -> f64 {
+        let g = g_weak();
+        let gz = g / cos_weak();
+        let contact = -g * gz * SIN_THETA_WEAK_SQRD / 2.0;
+        let tcoup = g * gz * (1.0 - 2.0 * SIN_THETA_WEAK_SQRD) / 4.0;
+        let ucoup = -g * gz / 4.0;
+        let gv = g * cos_weak();
+        sigma_vv(
+            cme,
+            ms,
+            self.mhc(),
+            W_BOSON_MASS,
+            Z_BOSON_MASS,
+            1.0,
+            |kin| shc_wv_amplitude(kin, mn, gv, contact, tcoup, ucoup),
+        )
+    }
+    /// Compute the cross section for S + H^+ -> W^+ + γ, where S is H0 or A0
+    /// with mass `ms`.
+    fn sigma_shc_wa(&self, cme: f64, ms: f64) -> f64 {
+        let g = g_weak();
+        let e = (4.0 * PI * ALPHA_EM).sqrt();
+        sigma_vv(cme, ms, self.mhc(), W_BOSON_MASS, 0.0, 1.0, |kin| {
+            shc_wv_amplitude(kin, ms, e, g * e / 2.0, g * e / 2.0, 0.0)
+        })
+    }
+    /// Compute the cross section for S + H^+ -> W^+ + h, where S is H0 or A0
+    /// with mass `ms` and hSS coupling `c v`.
+    fn sigma_shc_wh(&self, cme: f64, ms: f64, c: f64) -> f64 {
+        let g = g_weak();
+        let c2 = self.lam_3();
+        cross_section(cme, ms, self.mhc(), W_BOSON_MASS, HIGGS_MASS, 1.0, |kin| {
+            vh_msqrd(kin, W_BOSON_WIDTH, g, g * W_BOSON_MASS, c, c2)
+        })
+    }
+    /// Compute the cross section for H0 + H^+ -> W^+ + Z.
+    pub fn sigma_h0hc_wz(&self, cme: f64) -> f64 {
+        self.sigma_shc_wz(cme, self.mh0, self.ma0())
+    }
+    /// Compute the cross section for H0 + H^+ -> W^+ + γ.
+    pub fn sigma_h0hc_wa(&self, cme: f64) -> f64 {
+        self.sigma_shc_wa(cme, self.mh0)
+    }
+    /// Compute the cross section for H0 + H^+ -> W^+ + h.
+    pub fn sigma_h0hc_wh(&self, cme: f64) -> f64 {
+        self.sigma_shc_wh(cme, self.mh0, 2.0 * self.lam_l)
+    }
+    /// Compute the cross section for A0 + H^+ -> W^+ + Z.
+    pub fn sigma_a0hc_wz(&self, cme: f64) -> f64 {
+        self.sigma_shc_wz(cme, self.ma0(), self.mh0)
+    }
+    /// Compute the cross section for A0 + H^+ -> W^+ + γ.
+    pub fn sigma_a0hc_wa(&self, cme: f64) -> f64 {
+        self.sigma_shc_wa(cme, self.ma0())
+    }
+    /// Compute the cross section for A0 + H^+ -> W^+ + h.
+    pub fn sigma_a0hc_wh(&self, cme: f64) -> f64 {
+        self.sigma_shc_wh(cme, self.ma0(), 2.0 * self.lam_a())
+    }
+    /// Boson final states of species i + species j as the cross section,
+    /// its weight in `sigma_coann` and the center-of-mass energy at which
+    /// the channel opens.
+    fn boson_channels(&self, i: usize, j: usize) -> Vec<(Channel, f64, f64)> {
+        let (mw, mz, mh) = (W_BOSON_MASS, Z_BOSON_MASS, HIGGS_MASS);
+        let masses = [self.mh0, self.ma0(), self.mhc()];
+        let channels: Vec<(Channel, f64, f64)> = match (i.min(j), i.max(j)) {
+            (0, 0) => vec![
+                (InertDoublet::sigma_h0h0_ww, 1.0, mw),
+                (InertDoublet::sigma_h0h0_zz, 1.0, mz),
+                (InertDoublet::sigma_h0h0_hh, 1.0, 2.0 * mh),
+            ],
+            (1, 1) => vec![
+                (InertDoublet::sigma_a0a0_ww, 1.0, mw),
+                (InertDoublet::sigma_a0a0_zz, 1.0, mz),
+                (InertDoublet::sigma_a0a0_hh, 1.0, 2.0 * mh),
+            ],
+            // Averaged over H^+H^-, H^-H^+, H^+H^+ and H^-H^-
+            (2, 2) => vec![
+                (InertDoublet::sigma_hphm_ww, 0.5, mw),
+                (InertDoublet::sigma_hphm_zz, 0.5, mz),
+                (InertDoublet::sigma_hphm_aa, 0.5, 0.0),
+                (InertDoublet::sigma_hphm_za, 0.5, mz),
+                (InertDoublet::sigma_hphm_zh, 0.5, mz + mh),
+                (InertDoublet::sigma_hphm_hh, 0.5, 2.0 * mh),
+                (InertDoublet::sigma_hphp_ww, 0.5, mw),
+            ],
+            (0, 1) => vec![
+                (InertDoublet::sigma_h0a0_ww, 1.0, mw),
+                (InertDoublet::sigma_h0a0_zh, 1.0, mz + mh),
+            ],
+            (0, 2) => vec![
+                (InertDoublet::sigma_h0hc_wz, 1.0, mw + mz),
+                (InertDoublet::sigma_h0hc_wa, 1.0, mw),
+                (InertDoublet::sigma_h0hc_wh, 1.0, mw + mh),
+            ],
+            (1, 2) => vec![
+                (InertDoublet::sigma_a0hc_wz, 1.0, mw + mz),
+                (InertDoublet::sigma_a0hc_wa, 1.0, mw),
+                (InertDoublet::sigma_a0hc_wh, 1.0, mw + mh),
+            ],
+            _ => vec![],
+        };
+        let min = masses[i.min(2)] + masses[j.min(2)];
+        channels
+            .into_iter()
+            .map(|(sigma, wgt, thresh)| (sigma, wgt, thresh.max(min)))
+            .collect()
+    }
+    /// Compute the cross section for species i + species j into bosons, with
+    /// 0 = H0, 1 = A0 and 2 = H^±. Cross sections involving H^± are averaged
+    /// over the charges.
+    pub(super) fn sigma_bosons(&self, i: usize, j: usize, cme: f64) -> f64 {
+        self.boson_channels(i, j)
+            .iter()
+            .map(|&(sigma, wgt, _)| wgt * sigma(self, cme))
+            .sum()
+    }
+    /// Same as `sigma_bosons`, but interpolated from the tables computed by
+    /// `tabulate` if they match the current parameters.
+    pub(super) fn sigma_bosons_tabulated(&self, i: usize, j: usize, cme: f64) -> f64 {
+        let pair = PAIRS.iter().position(|&p| p == (i.min(j), i.max(j)));
+        match (&self.tables, pair) {
+            (Some(tables), Some(k)) if tables.params == self.params() => tables.pairs[k]
+                .iter()
+                .map(|table| table.eval(self, cme))
+                .sum(),
+            _ => self.sigma_bosons(i, j, cme),
+        }
+    }
+    fn params(&self) -> [f64; 4] {
+        [self.mh0, self.dma, self.dmc, self.lam_l]
+    }
+    /// Tabulate the cross sections into boson final states, so that the
+    /// Boltzmann solvers only need to interpolate them. Computing them
+    /// directly is slow, in particular with an off-shell W or Z. The tables
+    /// are ignored if the parameters are changed afterwards.
+    pub fn tabulate(mut self) -> InertDoublet {
+        let pairs = PAIRS
+            .iter()
+            .map(|&(i, j)| {
+                self.boson_channels(i, j)
+                    .into_iter()
+                    .map(|(sigma, wgt, thresh)| ChannelTable::new(&self, sigma, wgt, thresh))
+                    .collect()
+            })
+            .collect();
+        self.tables = Some(Arc::new(BosonTables {
+            params: self.params(),
+            pairs,
+        }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The gauge cancellations keep s σ from growing at high energies for the
+    /// vector final states.
+    #[test]
+    fn test_gauge_cancellation() {
+        let model = InertDoublet::new(300.0, 20.0, 60.0, 0.05);
+        let channels: [(&str, Channel); 15] = [
+            ("h0h0_ww", InertDoublet::sigma_h0h0_ww),
+            ("h0h0_zz", InertDoublet::sigma_h0h0_zz),
+            ("hphm_ww", InertDoublet::sigma_hphm_ww),
+            ("hphm_zz", InertDoublet::sigma_hphm_zz),
+            ("hphm_aa", InertDoublet::sigma_hphm_aa),
+            ("hphm_za", InertDoublet::sigma_hphm_za),
+            ("hphm_zh", InertDoublet::sigma_hphm_zh),
+            ("h0a0_ww", InertDoublet::sigma_h0a0_ww),
+            ("h0a0_zh", InertDoublet::sigma_h0a0_zh),
+            ("h0hc_wz", InertDoublet::sigma_h0hc_wz),
+            ("h0hc_wa", InertDoublet::sigma_h0hc_wa),
+            ("h0hc_wh", InertDoublet::sigma_h0hc_wh),
+            ("a0hc_wz", InertDoublet::sigma_a0hc_wz),
+            ("a0hc_wa", InertDoublet::sigma_a0hc_wa),
+            ("hphp_ww", InertDoublet::sigma_hphp_ww),
+        ];
+        for &(name, sigma) in channels.iter() {
+            let lo = 1e4f64.powi(2) * sigma(&model, 1e4);
+            let hi = 1e5f64.powi(2) * sigma(&model, 1e5);
+            assert!(lo > 0.0, "{}", name);
+            assert!(hi < 1.3 * lo, "{}: {} {}", name, lo, hi);
+        }
+    }
+
+    /// Well above threshold, the off-shell cross section reduces to the
+    /// on-shell one up to corrections of order Γ / M.
+    #[test]
+    fn test_off_shell_limit() {
+        let model = InertDoublet::new(200.0, 10.0, 10.0, 0.1);
+        let g = g_weak();
+        let cme = 500.0;
+        let on_shell = sigma_vv(cme, 200.0, 200.0, W_BOSON_MASS, W_BOSON_MASS, 1.0, |kin| {
+            scalar_exchange_amplitude(kin, g * g / 4.0, model.mhc(), 0.2, g * W_BOSON_MASS)
+        });
+        let full = model.sigma_h0h0_ww(cme);
+        assert!((full / on_shell - 1.0).abs() < 2.0 * W_BOSON_WIDTH / W_BOSON_MASS);
+    }
+
+    /// The tables interpolate the direct cross sections.
+    #[test]
+    fn test_channel_table() {
+        let model = InertDoublet::new(100.0, 10.0, 20.0, 0.05);
+        let thresh = model.mh0 + model.mhc();
+        let table = ChannelTable::new(&model, InertDoublet::sigma_h0hc_wa, 1.0, thresh);
+        for &cme in [221.0, 250.0, 400.0, 3000.0].iter() {
+            let direct = model.sigma_h0hc_wa(cme);
+            assert!((table.eval(&model, cme) / direct - 1.0).abs() < 1e-3);
+        }
+    }
+}
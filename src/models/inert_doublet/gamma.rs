@@ -0,0 +1,11 @@
+use super::InertDoublet;
+use haliax_constants::prelude::*;
+
+impl InertDoublet {
+    /// Squared matrix element for H0+f -> H0+f through t-channel Higgs
+    /// exchange, summed over the spins and colors of the fermion.
+    pub(super) fn elastic_amplitude_sqrd(&self, t: f64, mf: f64, ncol: f64) -> f64 {
+        ncol * (2.0 * self.lam_l * mf).powi(2) * 2.0 * (4.0 * mf * mf - t)
+            / (t - HIGGS_MASS * HIGGS_MASS).powi(2)
+    }
+}
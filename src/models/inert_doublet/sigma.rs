@@ -0,0 +1,223 @@
+use super::{sm_doublets, InertDoublet};
+use crate::models::sm_fermions;
+use crate::utils::integration::*;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+/// Magnitude of the center-of-mass momentum of particles with masses `m1`
+/// and `m2` given the squared center-of-mass energy `s`.
+pub(super) fn cm_momentum(s: f64, m1: f64, m2: f64) -> f64 {
+    ((s - (m1 + m2).powi(2)) * (s - (m1 - m2).powi(2)))
+        .max(0.0)
+        .sqrt()
+        / (2.0 * s.sqrt())
+}
+
+/// Real and imaginary parts of 1 / (s - M^2 + i M Γ).
+pub(super) fn propagator(s: f64, mass: f64, width: f64) -> (f64, f64) {
+    let re = s - mass * mass;
+    let im = mass * width;
+    let den = re * re + im * im;
+    (re / den, -im / den)
+}
+
+/// Weak coupling g = e / sin(θ_W).
+pub(super) fn g_weak() -> f64 {
+    (4.0 * std::f64::consts::PI * ALPHA_EM / SIN_THETA_WEAK_SQRD).sqrt()
+}
+
+impl InertDoublet {
+    /// Compute the cross section for S+S -> h^* -> f+fbar, where S has mass
+    /// `ms` and the hSS vertex is `c v`.
+    fn sigma_ss_ff_higgs(cme: f64, ms: f64, c: f64, mf: f64, ncol: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * ms {
+            let s = cme * cme;
+            let mh2 = HIGGS_MASS * HIGGS_MASS;
+            let beta = (1.0 - 4.0 * ms * ms / s).sqrt();
+            let beta_f = (1.0 - 4.0 * mf * mf / s).sqrt();
+            let prop = (s - mh2).powi(2) + mh2 * HIGGS_WIDTH.powi(2);
+            ncol * (c * mf).powi(2) * 2.0 * (s - 4.0 * mf * mf) * beta_f
+                / (16.0 * std::f64::consts::PI * s * beta * prop)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for S1(p1)+S2(p2) -> V^* -> f1+f2 through
+    /// vector bosons coupling to the scalar current (p1 - p2). `vv` and `aa`
+    /// are the complex (real, imaginary) effective vector and axial
+    /// couplings: the products of the scalar and fermion couplings with the
+    /// propagators, summed over the exchanged vectors. The longitudinal part
+    /// of the propagators is neglected.
+    #[allow(clippy::too_many_arguments)]
+    fn sigma_ss_ff_vector(
+        cme: f64,
+        m1: f64,
+        m2: f64,
+        mf1: f64,
+        mf2: f64,
+        ncol: f64,
+        vv: (f64, f64),
+        aa: (f64, f64),
+    ) -> f64 {
+        if cme <= m1 + m2 || cme <= mf1 + mf2 {
+            return 0.0;
+        }
+        let s = cme * cme;
+        let pi = cm_momentum(s, m1, m2);
+        let pf = cm_momentum(s, mf1, mf2);
+        let e1 = (s + m1 * m1 - m2 * m2) / (2.0 * cme);
+        let e2 = cme - e1;
+        let ef1 = (s + mf1 * mf1 - mf2 * mf2) / (2.0 * cme);
+        let ef2 = cme - ef1;
+
+        let v2 = vv.0 * vv.0 + vv.1 * vv.1;
+        let a2 = aa.0 * aa.0 + aa.1 * aa.1;
+        let q0 = e1 - e2;
+        let q2 = q0 * q0 - 4.0 * pi * pi;
+        let k1k2 = (s - mf1 * mf1 - mf2 * mf2) / 2.0;
+
+        let mut sum = 0.0;
+        for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+            let qk1 = q0 * ef1 - 2.0 * pi * pf * z;
+            let qk2 = q0 * ef2 + 2.0 * pi * pf * z;
+            let msqrd =
+                4.0 * (v2 + a2) * (2.0 * qk1 * qk2 - q2 * k1k2) - 4.0 * (v2 - a2) * mf1 * mf2 * q2;
+            sum += wgt * msqrd;
+        }
+        ncol * pf * sum / (32.0 * std::f64::consts::PI * s * pi)
+    }
+    /// Compute the cross section for H0+H0 -> h^* -> f+fbar.
+    pub fn sigma_h0h0_ff(&self, cme: f64, mf: f64, ncol: f64) -> f64 {
+        InertDoublet::sigma_ss_ff_higgs(cme, self.mh0, 2.0 * self.lam_l, mf, ncol)
+    }
+    /// Compute the cross section for A0+A0 -> h^* -> f+fbar.
+    pub fn sigma_a0a0_ff(&self, cme: f64, mf: f64, ncol: f64) -> f64 {
+        InertDoublet::sigma_ss_ff_higgs(cme, self.ma0(), 2.0 * self.lam_a(), mf, ncol)
+    }
+    /// Compute the cross section for H0+A0 -> Z^* -> f+fbar for a SM fermion
+    /// with electric charge `qf` and weak isospin `t3`.
+    pub fn sigma_h0a0_ff(&self, cme: f64, mf: f64, ncol: f64, qf: f64, t3: f64) -> f64 {
+        let gz = g_weak() / (2.0 * COS_THETA_WEAK);
+        let (re, im) = propagator(cme * cme, Z_BOSON_MASS, Z_BOSON_WIDTH);
+        let cv = gz * gz * (t3 - 2.0 * qf * SIN_THETA_WEAK_SQRD);
+        let ca = gz * gz * t3;
+        let vv = (cv * re, cv * im);
+        let aa = (ca * re, ca * im);
+        InertDoublet::sigma_ss_ff_vector(cme, self.mh0, self.ma0(), mf, mf, ncol, vv, aa)
+    }
+    /// Compute the cross section for H^+ + H^- -> f+fbar through the
+    /// s-channel photon, Z and Higgs.
+    pub fn sigma_hphm_ff(&self, cme: f64, mf: f64, ncol: f64, qf: f64, t3: f64) -> f64 {
+        let s = cme * cme;
+        let e2 = 4.0 * std::f64::consts::PI * ALPHA_EM;
+        let gz = g_weak() / (2.0 * COS_THETA_WEAK);
+        let cos_2w = 1.0 - 2.0 * SIN_THETA_WEAK_SQRD;
+        let (re, im) = propagator(s, Z_BOSON_MASS, Z_BOSON_WIDTH);
+        let cv = gz * gz * cos_2w * (t3 - 2.0 * qf * SIN_THETA_WEAK_SQRD);
+        let ca = gz * gz * cos_2w * t3;
+        let vv = (e2 * qf / s + cv * re, cv * im);
+        let aa = (ca * re, ca * im);
+        // The Higgs and vector amplitudes don't interfere after the angular
+        // integration.
+        InertDoublet::sigma_ss_ff_vector(cme, self.mhc(), self.mhc(), mf, mf, ncol, vv, aa)
+            + InertDoublet::sigma_ss_ff_higgs(cme, self.mhc(), self.lam_3(), mf, ncol)
+    }
+    /// Compute the cross section for S + H^+ -> W^* -> f+fbar', where S is
+    /// H0 or A0 with mass `ms`.
+    fn sigma_shc_ff(cme: f64, ms: f64, mhc: f64, mu: f64, md: f64, ncol: f64) -> f64 {
+        let gw = g_weak();
+        let (re, im) = propagator(cme * cme, W_BOSON_MASS, W_BOSON_WIDTH);
+        let c = gw / 2.0 * gw / (2.0 * std::f64::consts::SQRT_2);
+        let vv = (c * re, c * im);
+        InertDoublet::sigma_ss_ff_vector(cme, ms, mhc, mu, md, ncol, vv, vv)
+    }
+    /// Compute the cross section for H0 + H^+ -> W^* -> f+fbar'.
+    pub fn sigma_h0hc_ff(&self, cme: f64, mu: f64, md: f64, ncol: f64) -> f64 {
+        InertDoublet::sigma_shc_ff(cme, self.mh0, self.mhc(), mu, md, ncol)
+    }
+    /// Compute the cross section for A0 + H^+ -> W^* -> f+fbar'.
+    pub fn sigma_a0hc_ff(&self, cme: f64, mu: f64, md: f64, ncol: f64) -> f64 {
+        InertDoublet::sigma_shc_ff(cme, self.ma0(), self.mhc(), mu, md, ncol)
+    }
+    /// Compute the cross section for species i + species j into SM
+    /// fermions, with 0 = H0, 1 = A0 and 2 = H^±. Cross sections involving
+    /// H^± are averaged over the charges.
+    pub(super) fn sigma_fermions(&self, i: usize, j: usize, cme: f64) -> f64 {
+        let fermions = sm_fermions();
+        match (i.min(j), i.max(j)) {
+            (0, 0) => fermions
+                .iter()
+                .map(|f| self.sigma_h0h0_ff(cme, f.mass, f.ncol))
+                .sum(),
+            (1, 1) => fermions
+                .iter()
+                .map(|f| self.sigma_a0a0_ff(cme, f.mass, f.ncol))
+                .sum(),
+            // H^+H^+ and H^-H^- don't annihilate into fermions.
+            (2, 2) => {
+                fermions
+                    .iter()
+                    .map(|f| self.sigma_hphm_ff(cme, f.mass, f.ncol, f.charge, f.t3))
+                    .sum::<f64>()
+                    / 2.0
+            }
+            (0, 1) => fermions
+                .iter()
+                .map(|f| self.sigma_h0a0_ff(cme, f.mass, f.ncol, f.charge, f.t3))
+                .sum(),
+            (0, 2) => sm_doublets()
+                .iter()
+                .map(|&(_, mu, md, ncol)| self.sigma_h0hc_ff(cme, mu, md, ncol))
+                .sum(),
+            (1, 2) => sm_doublets()
+                .iter()
+                .map(|&(_, mu, md, ncol)| self.sigma_a0hc_ff(cme, mu, md, ncol))
+                .sum(),
+            _ => 0.0,
+        }
+    }
+    /// Compute the total H0+H0 annihilation cross section.
+    pub fn sigma_h0h0(&self, cme: f64) -> f64 {
+        self.sigma_h0h0_channels(cme).values().sum()
+    }
+    /// Compute the H0+H0 annihilation cross section into each final state.
+    pub fn sigma_h0h0_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        for f in sm_fermions().iter() {
+            channels.insert(f.name, self.sigma_h0h0_ff(cme, f.mass, f.ncol));
+        }
+        channels.insert("WW", self.sigma_h0h0_ww(cme));
+        channels.insert("ZZ", self.sigma_h0h0_zz(cme));
+        channels.insert("hh", self.sigma_h0h0_hh(cme));
+        channels
+    }
+    /// Compute the total A0+A0 annihilation cross section.
+    pub fn sigma_a0a0(&self, cme: f64) -> f64 {
+        self.sigma_fermions(1, 1, cme) + self.sigma_bosons(1, 1, cme)
+    }
+    /// Compute the total H0+A0 coannihilation cross section.
+    pub fn sigma_h0a0(&self, cme: f64) -> f64 {
+        self.sigma_fermions(0, 1, cme) + self.sigma_bosons(0, 1, cme)
+    }
+    /// Compute the total H^+ + H^- annihilation cross section.
+    pub fn sigma_hphm(&self, cme: f64) -> f64 {
+        sm_fermions()
+            .iter()
+            .map(|f| self.sigma_hphm_ff(cme, f.mass, f.ncol, f.charge, f.t3))
+            .sum::<f64>()
+            + self.sigma_hphm_ww(cme)
+            + self.sigma_hphm_zz(cme)
+            + self.sigma_hphm_aa(cme)
+            + self.sigma_hphm_za(cme)
+            + self.sigma_hphm_zh(cme)
+            + self.sigma_hphm_hh(cme)
+    }
+    /// Compute the total H0 + H^+ coannihilation cross section.
+    pub fn sigma_h0hc(&self, cme: f64) -> f64 {
+        self.sigma_fermions(0, 2, cme) + self.sigma_bosons(0, 2, cme)
+    }
+    /// Compute the total A0 + H^+ coannihilation cross section.
+    pub fn sigma_a0hc(&self, cme: f64) -> f64 {
+        self.sigma_fermions(1, 2, cme) + self.sigma_bosons(1, 2, cme)
+    }
+}
@@ -0,0 +1,37 @@
+use super::ScalarSinglet;
+use crate::constants::GEV2_TO_CM2;
+use crate::direct_detection::*;
+use haliax_constants::prelude::*;
+
+impl ScalarSinglet {
+    /// Compute the spin-independent DM-nucleon cross section in cm^2:
+    ///     σ_SI = λ_hs^2 f_N^2 μ_N^2 m_N^2 / (4π m_h^4 m_S^2).
+    pub fn sigma_si(&self, nucleon: &NucleonMatrixElements) -> f64 {
+        let mn = nucleon.mass;
+        let mu = reduced_mass(self.ms, mn);
+        let sigma = self.lam_hs.powi(2) * nucleon.f_n().powi(2) * mu.powi(2) * mn.powi(2)
+            / (4.0 * std::f64::consts::PI * HIGGS_MASS.powi(4) * self.ms.powi(2));
+        sigma * GEV2_TO_CM2
+    }
+    /// Compute the spin-independent DM-nucleon cross section in cm^2 rescaled
+    /// by Ω / Ω_obs, where `omega_h2` is the relic density of the scalar.
+    pub fn sigma_si_rescaled(&self, nucleon: &NucleonMatrixElements, omega_h2: f64) -> f64 {
+        rescale_by_relic_density(self.sigma_si(nucleon), omega_h2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Benchmark from eq. (3.3) of Cline et al., arXiv:1306.4710, with
+    /// f_N = 0.30 and m_h = 125 GeV: m_S = 100 GeV and λ_hs = 0.1 give
+    /// σ_SI ≈ 8.7e-45 cm^2.
+    #[test]
+    fn test_sigma_si_benchmark() {
+        let nucleon = NucleonMatrixElements::new(PROTON_MASS, 0.0, 0.0, 0.1);
+        assert!((nucleon.f_n() - 0.30).abs() < 1e-12);
+        let sigma = ScalarSinglet::new(100.0, 0.1).sigma_si(&nucleon);
+        assert!((sigma / 8.69e-45 - 1.0).abs() < 0.02, "σ_SI = {:e}", sigma);
+    }
+}
@@ -1,5 +1,7 @@
 use super::ScalarSinglet;
+use crate::models::sm_fermions;
 use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
 
 impl ScalarSinglet {
     #[allow(dead_code)]
@@ -122,17 +124,18 @@ impl ScalarSinglet {
         }
     }
     pub fn sigma_ss(&self, cme: f64) -> f64 {
-        self.sigma_ss_ff(cme, TOP_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, CHARM_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, UP_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, BOTTOM_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, STRANGE_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, DOWN_QUARK_MASS, 3.0)
-            + self.sigma_ss_ff(cme, TAU_MASS, 1.0)
-            + self.sigma_ss_ff(cme, MUON_MASS, 1.0)
-            + self.sigma_ss_ff(cme, ELECTRON_MASS, 1.0)
-            + self.sigma_ss_zz(cme)
-            + self.sigma_ss_ww(cme)
-            + self.sigma_ss_hh(cme)
+        self.sigma_ss_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_ss_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        for f in sm_fermions().iter().filter(|f| f.mass > 0.0) {
+            channels.insert(f.name, self.sigma_ss_ff(cme, f.mass, f.ncol));
+        }
+        channels.insert("ZZ", self.sigma_ss_zz(cme));
+        channels.insert("WW", self.sigma_ss_ww(cme));
+        channels.insert("hh", self.sigma_ss_hh(cme));
+        channels
     }
 }
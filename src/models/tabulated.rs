@@ -0,0 +1,237 @@
+//! Model defined by tables of σ(√s) and γ(T) read from files. Each table is a
+//! text file with two columns separated by whitespace, commas or semicolons.
+//! Lines starting with '#' are comments, except for metadata lines of the form
+//!     # mass = 100.0
+//!     # g = 2
+//!     # statistics = fermion
+//! in the cross-section file, which fix the DM mass in GeV, its internal
+//! d.o.f. and its statistics ("fermion", "boson" or "maxwell-boltzmann").
+//! The data may be preceded by header lines with non-numeric column names;
+//! any other line which can't be parsed is reported as an error.
+
+use super::TabulatedModel;
+use crate::boltz::traits::{DarkMatterModel, Statistics};
+use crate::utils::interpolation::{InterpolationError, LogSpline};
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufReader, Error, ErrorKind};
+use std::path::Path;
+
+/// Two-column table along with the metadata found in its header.
+struct Table {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    metadata: Vec<(String, String)>,
+}
+
+fn read_table<P: AsRef<Path>>(path: P) -> std::io::Result<Table> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut table = Table {
+        xs: vec![],
+        ys: vec![],
+        metadata: vec![],
+    };
+    let mut header = true;
+    for (lineno, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut kv = comment.splitn(2, '=');
+            if let (Some(k), Some(v)) = (kv.next(), kv.next()) {
+                table
+                    .metadata
+                    .push((k.trim().to_lowercase(), v.trim().to_lowercase()));
+            }
+            continue;
+        }
+        let cols: Vec<&str> = line
+            .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+            .filter(|c| !c.is_empty())
+            .collect();
+        let parsed: Vec<Option<f64>> = cols.iter().take(2).map(|c| c.parse::<f64>().ok()).collect();
+        match parsed.as_slice() {
+            [Some(x), Some(y)] => {
+                table.xs.push(*x);
+                table.ys.push(*y);
+                header = false;
+            }
+            _ if header && parsed.iter().all(Option::is_none) => {}
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "line {}: expected two numeric columns, found '{}'",
+                        lineno + 1,
+                        line
+                    ),
+                ))
+            }
+        }
+    }
+    if table.xs.len() < 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "table needs at least two points",
+        ));
+    }
+    Ok(table)
+}
+
+fn parse_statistics(s: &str) -> Option<Statistics> {
+    match s {
+        "fermion" | "fermi-dirac" | "fd" => Some(Statistics::Fermion),
+        "boson" | "bose-einstein" | "be" => Some(Statistics::Boson),
+        "maxwell-boltzmann" | "mb" => Some(Statistics::MaxwellBoltzmann),
+        _ => None,
+    }
+}
+
+impl TabulatedModel {
+    /// Construct the model from the annihilation cross section `sigmas` (in
+    /// GeV^-2) at center-of-mass energies `cmes` and the momentum exchange
+    /// rate `gammas` (in GeV) at SM temperatures `temps`. Fails if either
+    /// table can't be interpolated.
+    pub fn new(
+        mx: f64,
+        g: f64,
+        statistics: Statistics,
+        cmes: &[f64],
+        sigmas: &[f64],
+        temps: &[f64],
+        gammas: &[f64],
+    ) -> Result<TabulatedModel, InterpolationError> {
+        Ok(TabulatedModel {
+            mx,
+            g,
+            statistics,
+            sigma: LogSpline::new(cmes, sigmas)?,
+            gamma: LogSpline::new(temps, gammas)?,
+        })
+    }
+    /// Read the model from the cross-section table at `sigma_path` and the
+    /// momentum-exchange-rate table at `gamma_path`. The mass, d.o.f. and
+    /// statistics are read from the header of the cross-section table.
+    pub fn from_files<P: AsRef<Path>, Q: AsRef<Path>>(
+        sigma_path: P,
+        gamma_path: Q,
+    ) -> std::io::Result<TabulatedModel> {
+        let sigma = read_table(sigma_path)?;
+        let gamma = read_table(gamma_path)?;
+
+        let lookup = |key: &str| -> std::io::Result<&str> {
+            sigma
+                .metadata
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+                .ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!("missing metadata '{}'", key),
+                    )
+                })
+        };
+        let invalid = |key: &str| Error::new(ErrorKind::InvalidData, format!("invalid '{}'", key));
+
+        let mx = lookup("mass")?
+            .parse::<f64>()
+            .map_err(|_| invalid("mass"))?;
+        let g = lookup("g")?.parse::<f64>().map_err(|_| invalid("g"))?;
+        let statistics =
+            parse_statistics(lookup("statistics")?).ok_or_else(|| invalid("statistics"))?;
+
+        TabulatedModel::new(
+            mx, g, statistics, &sigma.xs, &sigma.ys, &gamma.xs, &gamma.ys,
+        )
+        .map_err(Error::from)
+    }
+}
+
+impl DarkMatterModel for TabulatedModel {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        self.g
+    }
+    fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+    /// Interpolated cross section. Below the first tabulated energy the cross
+    /// section vanishes, above the last it is extrapolated as a power law.
+    fn sigma(&self, cme: f64) -> f64 {
+        if cme < self.sigma.xmin() {
+            0.0
+        } else {
+            self.sigma.eval(cme)
+        }
+    }
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        self.gamma.eval(self.mx / x)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write_file(name: &str, contents: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_from_files() {
+        let sigma_path = write_file(
+            "test_tabulated_sigma.dat",
+            "# Mass = 100.0\n# g = 2\n# statistics = Fermion\ncme sigma\n\
+             200.0 1e-9\n300.0, 2e-9\n# comment\n\n400.0; 4e-9\n",
+        );
+        let gamma_path = write_file(
+            "test_tabulated_gamma.dat",
+            "1.0 1e-20\n10.0 1e-15\n100.0 1e-10\n",
+        );
+        let model = TabulatedModel::from_files(&sigma_path, &gamma_path).unwrap();
+        assert_eq!(model.mass(), 100.0);
+        assert_eq!(model.g(), 2.0);
+        assert_eq!(model.statistics(), Statistics::Fermion);
+        for &(cme, sigma) in [(200.0, 1e-9), (300.0, 2e-9), (400.0, 4e-9)].iter() {
+            assert!((model.sigma(cme) / sigma - 1.0).abs() < 1e-10);
+        }
+        for &(temp, gamma) in [(1.0, 1e-20), (10.0, 1e-15), (100.0, 1e-10)].iter() {
+            assert!((model.momentum_exchange_rate(100.0 / temp) / gamma - 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let gamma_path = write_file(
+            "test_tabulated_invalid_gamma.dat",
+            "1.0 1e-20\n10.0 1e-15\n",
+        );
+        let from_sigma = |contents: &str| {
+            let path = write_file("test_tabulated_invalid_sigma.dat", contents);
+            TabulatedModel::from_files(&path, &gamma_path)
+                .err()
+                .unwrap()
+        };
+
+        let err = from_sigma(
+            "# mass = 100\n# g = 2\n# statistics = boson\n200 1e-9\n300 2e-9x\n400 4e-9\n",
+        );
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 5:"), "{}", err);
+
+        let err = from_sigma("# mass = 100\n# g = 2\n200 1e-9\n300 2e-9\n");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "missing metadata 'statistics'");
+
+        let err = from_sigma("# mass = heavy\n# g = 2\n# statistics = mb\n200 1e-9\n300 2e-9\n");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "invalid 'mass'");
+    }
+}
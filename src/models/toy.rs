@@ -1,22 +1,29 @@
 use super::ToyModel;
-use crate::boltz::traits::FullBoltzmann;
+use crate::boltz::helper::hubblet;
+use crate::boltz::traits::{DarkMatterModel, Statistics};
 
-impl FullBoltzmann for ToyModel {
-    fn feq(&self, x: f64, q: f64) -> f64 {
-        (-(x * x + q * q).sqrt()).exp()
+impl DarkMatterModel for ToyModel {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        1.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::MaxwellBoltzmann
+    }
+    /// Cross section reproducing the constant piece `c0` of sigma*v. The
+    /// temperature-dependent piece is only included in `sigmav_angle_avg`.
+    fn sigma(&self, cme: f64) -> f64 {
+        let beta = (1.0 - 4.0 * self.mx * self.mx / (cme * cme)).sqrt();
+        self.c0 / (2.0 * beta)
     }
-    fn gamma_hinv(&self, temp: f64) -> f64 {
+    fn momentum_exchange_rate(&self, temp: f64) -> f64 {
         let x = self.mx / temp;
-        100.0 * (10.0 / x).powi(3)
+        100.0 * (10.0 / x).powi(3) * hubblet(self.mx / temp)
     }
-    fn sigmav(&self, x: f64, q: f64, qt: f64) -> f64 {
+    fn sigmav_angle_avg(&self, x: f64, q: f64, qt: f64) -> f64 {
         let t = (x * x + q * q) * (x * x + qt * qt);
         self.c0 + self.c1 * (1.0 - x.powi(4) / (q * qt * t.sqrt()) * (q * qt / t.sqrt()).atanh())
     }
-    fn dm_mass(&self) -> f64 {
-        self.mx
-    }
-    fn g(&self) -> f64 {
-        1.0
-    }
 }
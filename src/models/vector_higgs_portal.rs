@@ -0,0 +1,79 @@
+pub mod gamma;
+pub mod sigma;
+
+use super::{sm_fermions, VectorHiggsPortal};
+use crate::boltz::traits::{fermion_momentum_exchange_rate, DarkMatterModel, Statistics};
+use crate::observables::Annihilation;
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+impl VectorHiggsPortal {
+    pub fn new(mv: f64, lam: f64) -> VectorHiggsPortal {
+        VectorHiggsPortal { mv, lam_hv: lam }
+    }
+}
+
+impl DarkMatterModel for VectorHiggsPortal {
+    fn mass(&self) -> f64 {
+        self.mv
+    }
+    fn g(&self) -> f64 {
+        3.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Boson
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_vv(cme)
+    }
+    /// Compute the momentum exchange rate between the DM and SM.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        let fermions = sm_fermions().into_iter().filter(|f| f.mass > 0.0);
+        fermion_momentum_exchange_rate(self.mv, self.g(), x, fermions, |f, _, t| {
+            self.elastic_amplitude_sqrd(t, f.mass, f.ncol)
+        })
+    }
+    /// The Higgs resonance and the hh threshold.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![HIGGS_MASS / self.mv, 2.0 * HIGGS_MASS / self.mv]
+    }
+}
+
+impl Annihilation for VectorHiggsPortal {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_vv_channels(cme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::boltz::cosmology::StandardCosmology;
+    use crate::boltz::simple::integrate_simple_boltzmann;
+
+    /// Far above the electroweak scale, the longitudinal W and Z behave as
+    /// the Goldstones in H, so V+V -> Z+Z matches V+V -> h+h and
+    /// V+V -> W^+ + W^- is twice as large.
+    #[test]
+    fn test_goldstone_equivalence() {
+        let model = VectorHiggsPortal::new(5e3, 0.3);
+        let cme = 1.2e4;
+        let hh = model.sigma_vv_hh(cme);
+        assert!((model.sigma_vv_zz(cme) / hh - 1.0).abs() < 1e-3);
+        assert!((model.sigma_vv_ww(cme) / (2.0 * hh) - 1.0).abs() < 1e-3);
+    }
+
+    /// The relic density scales roughly as 1 / λ_hv^2.
+    #[test]
+    fn test_relic_density() {
+        let omega = |lam: f64| {
+            let model = VectorHiggsPortal::new(1e3, lam);
+            let sol = integrate_simple_boltzmann(model, &StandardCosmology, 1.0, 1e3);
+            sol.us[sol.us.len() - 1][0].exp() * 1e3 * S_TODAY / RHO_CRIT
+        };
+        let (omega1, omega2) = (omega(0.5), omega(1.0));
+        assert!(omega1.is_finite() && omega2 > 0.0);
+        assert!(omega1 / omega2 > 3.0 && omega1 / omega2 < 5.0);
+    }
+}
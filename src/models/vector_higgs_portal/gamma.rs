@@ -0,0 +1,14 @@
+use super::VectorHiggsPortal;
+use haliax_constants::prelude::*;
+
+impl VectorHiggsPortal {
+    /// Squared matrix element for V+f -> V+f through t-channel Higgs
+    /// exchange, summed over the polarizations, spins and colors of all
+    /// particles.
+    pub(super) fn elastic_amplitude_sqrd(&self, t: f64, mf: f64, ncol: f64) -> f64 {
+        let m2 = self.mv * self.mv;
+        let dm = 2.0 + (m2 - t / 2.0).powi(2) / (m2 * m2);
+        let sm = 2.0 * (4.0 * mf * mf - t);
+        ncol * (self.lam_hv * mf).powi(2) * dm * sm / (t - HIGGS_MASS * HIGGS_MASS).powi(2)
+    }
+}
@@ -0,0 +1,86 @@
+use super::{sm_fermions, VectorHiggsPortal};
+use haliax_constants::prelude::*;
+use std::collections::BTreeMap;
+
+impl VectorHiggsPortal {
+    /// Polarization sum |ε1.ε2|^2 averaged over the initial polarizations
+    /// given the squared center-of-mass energy `s`.
+    fn polarization_avg(&self, s: f64) -> f64 {
+        let m2 = self.mv * self.mv;
+        (2.0 + (s - 2.0 * m2).powi(2) / (4.0 * m2 * m2)) / 9.0
+    }
+    /// Compute the cross section for V+V -> h^* -> X given the width `width`
+    /// of an off-shell Higgs with mass `cme` into X.
+    fn sigma_vv_schannel(&self, cme: f64, width: f64) -> f64 {
+        let s = cme * cme;
+        let mh2 = HIGGS_MASS * HIGGS_MASS;
+        let beta = (1.0 - 4.0 * self.mv * self.mv / s).sqrt();
+        let prop = (s - mh2).powi(2) + mh2 * HIGGS_WIDTH.powi(2);
+        (self.lam_hv * HIGGS_VEV).powi(2) * self.polarization_avg(s) * width / (cme * beta * prop)
+    }
+    /// Compute the cross section for V+V -> f+fbar.
+    pub fn sigma_vv_ff(&self, cme: f64, mf: f64, ncol: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * self.mv {
+            let beta_f = (1.0 - 4.0 * mf * mf / (cme * cme)).sqrt();
+            let width = ncol * mf * mf * cme * beta_f.powi(3)
+                / (8.0 * std::f64::consts::PI * HIGGS_VEV.powi(2));
+            self.sigma_vv_schannel(cme, width)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for V+V -> B+B, where B is a massive SM
+    /// vector boson with mass `mb`. `sym` is 1 for W^+W^- and 1/2 for ZZ.
+    fn sigma_vv_bb(&self, cme: f64, mb: f64, sym: f64) -> f64 {
+        if cme > 2.0 * mb && cme > 2.0 * self.mv {
+            let r = mb * mb / (cme * cme);
+            let width = sym * cme.powi(3) * (1.0 - 4.0 * r).sqrt() * (1.0 - 4.0 * r + 12.0 * r * r)
+                / (16.0 * std::f64::consts::PI * HIGGS_VEV.powi(2));
+            self.sigma_vv_schannel(cme, width)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for V+V -> W^+ + W^-.
+    pub fn sigma_vv_ww(&self, cme: f64) -> f64 {
+        self.sigma_vv_bb(cme, W_BOSON_MASS, 1.0)
+    }
+    /// Compute the cross section for V+V -> Z + Z.
+    pub fn sigma_vv_zz(&self, cme: f64) -> f64 {
+        self.sigma_vv_bb(cme, Z_BOSON_MASS, 0.5)
+    }
+    /// Compute the cross section for V+V -> h + h from the contact
+    /// interaction and the s-channel Higgs. The t- and u-channel V exchange
+    /// diagrams are suppressed by λ_hv v^2 / m_V^2 and are neglected.
+    pub fn sigma_vv_hh(&self, cme: f64) -> f64 {
+        if cme > 2.0 * HIGGS_MASS && cme > 2.0 * self.mv {
+            let s = cme * cme;
+            let mh2 = HIGGS_MASS * HIGGS_MASS;
+            let beta = (1.0 - 4.0 * self.mv * self.mv / s).sqrt();
+            let beta_h = (1.0 - 4.0 * mh2 / s).sqrt();
+            let gam2 = mh2 * HIGGS_WIDTH.powi(2);
+            // |1 + 3 mh^2 / (s - mh^2 + i mh Γh)|^2
+            let amp = ((s + 2.0 * mh2).powi(2) + gam2) / ((s - mh2).powi(2) + gam2);
+            self.lam_hv.powi(2) * self.polarization_avg(s) * amp * beta_h
+                / (32.0 * std::f64::consts::PI * s * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the total annihilation cross section.
+    pub fn sigma_vv(&self, cme: f64) -> f64 {
+        self.sigma_vv_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_vv_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        // Only the Higgs is exchanged, so the neutrinos don't contribute.
+        for f in sm_fermions().iter().filter(|f| f.mass > 0.0) {
+            channels.insert(f.name, self.sigma_vv_ff(cme, f.mass, f.ncol));
+        }
+        channels.insert("ZZ", self.sigma_vv_zz(cme));
+        channels.insert("WW", self.sigma_vv_ww(cme));
+        channels.insert("hh", self.sigma_vv_hh(cme));
+        channels
+    }
+}
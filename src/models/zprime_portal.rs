@@ -0,0 +1,220 @@
+pub mod gamma;
+pub mod sigma;
+pub mod width;
+
+use super::{sm_fermions, SmFermion, ZPrimeCharges, ZPrimePortal};
+use crate::boltz::traits::{fermion_momentum_exchange_rate, DarkMatterModel, Statistics};
+use crate::observables::Annihilation;
+use std::collections::BTreeMap;
+
+impl ZPrimeCharges {
+    /// Vector and axial charges of the quarks and of the charged leptons.
+    fn quark_lepton(&self) -> ((f64, f64), (f64, f64)) {
+        match *self {
+            ZPrimeCharges::Universal => ((1.0, 0.0), (1.0, 0.0)),
+            ZPrimeCharges::BMinusL => ((1.0 / 3.0, 0.0), (-1.0, 0.0)),
+            ZPrimeCharges::Leptophobic => ((1.0, 0.0), (0.0, 0.0)),
+            ZPrimeCharges::Custom {
+                quark_v,
+                quark_a,
+                lepton_v,
+                lepton_a,
+            } => ((quark_v, quark_a), (lepton_v, lepton_a)),
+        }
+    }
+}
+
+/// Vector and axial couplings to the Z' of the SM fermion `f`, given the
+/// gauge coupling `g_zp` and the charges `charges`.
+pub(super) fn couplings(g_zp: f64, charges: ZPrimeCharges, f: &SmFermion) -> (f64, f64) {
+    let ((qv, qa), (lv, la)) = charges.quark_lepton();
+    if f.ncol == 3.0 {
+        (g_zp * qv, g_zp * qa)
+    } else if f.charge != 0.0 {
+        (g_zp * lv, g_zp * la)
+    } else {
+        // Only the left-handed neutrinos couple: (v - aγ5) -> (v + a) P_L.
+        let nu = g_zp * (lv + la) / 2.0;
+        (nu, nu)
+    }
+}
+
+impl ZPrimePortal {
+    pub fn new(
+        mx: f64,
+        mzp: f64,
+        gx_v: f64,
+        gx_a: f64,
+        g_zp: f64,
+        charges: ZPrimeCharges,
+    ) -> ZPrimePortal {
+        ZPrimePortal {
+            mx,
+            mzp,
+            gx_v,
+            gx_a,
+            g_zp,
+            charges,
+            width_zp: ZPrimePortal::compute_width_zp(mx, mzp, gx_v, gx_a, g_zp, charges),
+        }
+    }
+}
+
+impl DarkMatterModel for ZPrimePortal {
+    fn mass(&self) -> f64 {
+        self.mx
+    }
+    fn g(&self) -> f64 {
+        2.0
+    }
+    fn statistics(&self) -> Statistics {
+        Statistics::Fermion
+    }
+    fn sigma(&self, cme: f64) -> f64 {
+        self.sigma_xx(cme)
+    }
+    /// Compute the momentum exchange rate between the DM and SM.
+    fn momentum_exchange_rate(&self, x: f64) -> f64 {
+        let fermions = sm_fermions().into_iter().filter(|f| {
+            let (qv, qa) = couplings(self.g_zp, self.charges, f);
+            qv != 0.0 || qa != 0.0
+        });
+        fermion_momentum_exchange_rate(self.mx, self.g(), x, fermions, |f, w, t| {
+            let (qv, qa) = couplings(self.g_zp, self.charges, f);
+            self.elastic_amplitude_sqrd(w, t, f.mass, f.ncol, qv, qa)
+        })
+    }
+    /// The Z' resonance.
+    fn singular_points(&self) -> Vec<f64> {
+        vec![self.mzp / self.mx]
+    }
+}
+
+impl Annihilation for ZPrimePortal {
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        self.sigma_xx_channels(cme)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::models::DarkPhoton;
+    use crate::observables::cme_from_vrel;
+    use std::f64::consts::PI;
+
+    fn fermion(name: &str) -> SmFermion {
+        sm_fermions().into_iter().find(|f| f.name == name).unwrap()
+    }
+
+    /// σv for chi+chibar -> f+fbar at relative velocity `vrel`.
+    fn sigmav_ff(model: &ZPrimePortal, f: &SmFermion, vrel: f64) -> f64 {
+        let (qv, qa) = couplings(model.g_zp, model.charges, f);
+        model.sigma_xx_ff(cme_from_vrel(model.mx, vrel), f.mass, f.ncol, qv, qa) * vrel
+    }
+
+    /// On the Z' pole the cross section is the Breit-Wigner peak
+    ///     σ = 12π / (s β^2) Γ(Z' -> chi+chibar) Γ(Z' -> f+fbar) / Γ^2.
+    #[test]
+    fn test_resonance_peak() {
+        let charges = ZPrimeCharges::Custom {
+            quark_v: 1.0,
+            quark_a: 0.5,
+            lepton_v: 0.3,
+            lepton_a: 1.0,
+        };
+        let model = ZPrimePortal::new(100.0, 500.0, 0.3, 0.2, 0.1, charges);
+        let s = model.mzp * model.mzp;
+        let beta2 = 1.0 - 4.0 * model.mx * model.mx / s;
+        let width_in =
+            ZPrimePortal::width_v_to_ff(model.mzp, model.gx_v, model.gx_a, model.mx, 1.0);
+        for f in sm_fermions().iter().filter(|f| 2.0 * f.mass < model.mzp) {
+            let (qv, qa) = couplings(model.g_zp, model.charges, f);
+            let width_out = ZPrimePortal::width_v_to_ff(model.mzp, qv, qa, f.mass, f.ncol);
+            let expected = 12.0 * PI / (s * beta2) * width_in * width_out / model.width_zp.powi(2);
+            let sigma = model.sigma_xx_ff(model.mzp, f.mass, f.ncol, qv, qa);
+            assert!((sigma - expected).abs() < 1e-10 * expected, "{}", f.name);
+        }
+    }
+
+    /// The s-wave cross sections for pure vector and pure axial couplings:
+    ///     σv_VV = N_c g_v^2 q_v^2 (2 m^2 + m_f^2) β_f / (2π P),
+    ///     σv_AA = N_c g_a^2 q_a^2 m_f^2 β_f (M^2 - 4 m^2)^2 / (2π M^4 P),
+    /// with P = (M^2 - 4 m^2)^2 + M^2 Γ^2, while an axial DM coupling to a
+    /// vector current is p-wave.
+    #[test]
+    fn test_vector_axial_limits() {
+        let (mx, mzp, vrel) = (50.0, 1000.0, 1e-4);
+        let bb = fermion("bb");
+        let beta_f = (1.0 - (bb.mass / mx).powi(2)).sqrt();
+        let prop = |model: &ZPrimePortal| {
+            (mzp * mzp - 4.0 * mx * mx).powi(2) + (mzp * model.width_zp).powi(2)
+        };
+
+        let vv = ZPrimePortal::new(mx, mzp, 0.3, 0.0, 0.2, ZPrimeCharges::Universal);
+        let expected = 3.0 * (0.3 * 0.2_f64).powi(2) * (2.0 * mx * mx + bb.mass.powi(2)) * beta_f
+            / (2.0 * PI * prop(&vv));
+        let sigmav = sigmav_ff(&vv, &bb, vrel);
+        assert!((sigmav / expected - 1.0).abs() < 1e-4, "VV: {:e}", sigmav);
+
+        let axial = ZPrimeCharges::Custom {
+            quark_v: 0.0,
+            quark_a: 1.0,
+            lepton_v: 0.0,
+            lepton_a: 1.0,
+        };
+        let aa = ZPrimePortal::new(mx, mzp, 0.0, 0.3, 0.2, axial);
+        let expected =
+            3.0 * (0.3 * 0.2 * bb.mass).powi(2) * beta_f * (mzp * mzp - 4.0 * mx * mx).powi(2)
+                / (2.0 * PI * mzp.powi(4) * prop(&aa));
+        let sigmav = sigmav_ff(&aa, &bb, vrel);
+        assert!((sigmav / expected - 1.0).abs() < 1e-4, "AA: {:e}", sigmav);
+
+        let av = ZPrimePortal::new(mx, mzp, 0.0, 0.3, 0.2, ZPrimeCharges::Universal);
+        let ratio = sigmav_ff(&av, &bb, 2e-3) / sigmav_ff(&av, &bb, 1e-3);
+        assert!((ratio - 4.0).abs() < 1e-3, "AV: {}", ratio);
+    }
+
+    /// Under B - L the neutrinos couple with charge -1 but only through
+    /// their left-handed component, so each flavor gets half the rate of the
+    /// charged lepton, while the quarks have charge 1/3.
+    #[test]
+    fn test_b_minus_l_couplings() {
+        let model = ZPrimePortal::new(100.0, 1000.0, 0.3, 0.0, 0.1, ZPrimeCharges::BMinusL);
+        let channels = model.sigma_xx_channels(500.0);
+        let ee = channels["ee"];
+        for nu in ["nunu_e", "nunu_mu", "nunu_tau"].iter() {
+            assert!((channels[nu] / ee - 0.5).abs() < 1e-8);
+        }
+        assert!((channels["uu"] / ee - 1.0 / 3.0).abs() < 1e-8);
+    }
+
+    /// With vector couplings only, chi+chibar -> Z'+Z' matches the dark
+    /// photon result. For massless DM the two chiralities decouple, giving
+    ///     σ(g_v, g_a) = σ(1, 0) ((g_v + g_a)^4 + (g_v - g_a)^4) / 2.
+    #[test]
+    fn test_zpzp_limits() {
+        let (mx, mzp, gv) = (100.0, 30.0, 0.5);
+        let zp = ZPrimePortal::new(mx, mzp, gv, 0.0, 0.1, ZPrimeCharges::Universal);
+        let ap = DarkPhoton::new(mx, mzp, gv * gv / (4.0 * PI), 1e-3);
+        for &cme in [250.0, 500.0, 2000.0].iter() {
+            let sigma = zp.sigma_xx_zpzp(cme);
+            assert!((sigma - ap.sigma_xx_apap(cme)).abs() < 1e-8 * sigma);
+        }
+
+        let (mx, mzp, gv, ga) = (1e-3, 10.0, 0.3, 0.2_f64);
+        let unit = ZPrimePortal::new(mx, mzp, 1.0, 0.0, 0.1, ZPrimeCharges::Universal);
+        let chiral = ZPrimePortal::new(mx, mzp, gv, ga, 0.1, ZPrimeCharges::Universal);
+        let factor = ((gv + ga).powi(4) + (gv - ga).powi(4)) / 2.0;
+        for &cme in [50.0, 200.0].iter() {
+            let expected = unit.sigma_xx_zpzp(cme) * factor;
+            let sigma = chiral.sigma_xx_zpzp(cme);
+            assert!(
+                (sigma / expected - 1.0).abs() < 1e-4,
+                "{} vs {}",
+                sigma,
+                expected
+            );
+        }
+    }
+}
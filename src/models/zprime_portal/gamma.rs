@@ -0,0 +1,43 @@
+use super::ZPrimePortal;
+
+impl ZPrimePortal {
+    /// Squared matrix element for chi+f -> chi+f through t-channel Z'
+    /// exchange, summed over the spins and colors of all particles, for a SM
+    /// fermion with energy `w` in the DM rest frame and vector and axial
+    /// couplings `qv` and `qa`.
+    pub(super) fn elastic_amplitude_sqrd(
+        &self,
+        w: f64,
+        t: f64,
+        mf: f64,
+        ncol: f64,
+        qv: f64,
+        qa: f64,
+    ) -> f64 {
+        let m2 = self.mx * self.mx;
+        let mf2 = mf * mf;
+        let mz2 = self.mzp * self.mzp;
+        let s = m2 + mf2 + 2.0 * self.mx * w;
+        let u = 2.0 * (m2 + mf2) - s - t;
+        let (gv, ga) = (self.gx_v, self.gx_a);
+
+        let base =
+            t * t + 2.0 * t * u + 2.0 * u * u - 4.0 * (m2 + mf2) * u + 2.0 * (m2 * m2 + mf2 * mf2);
+        let vv = base + 4.0 * m2 * mf2;
+        let va = base - 4.0 * m2 * mf2 - 4.0 * mf2 * t;
+        let av = base - 4.0 * m2 * mf2 - 4.0 * m2 * t;
+        // Includes the longitudinal part of the Z' propagator.
+        let aa = base - 4.0 * (m2 + mf2) * t
+            + 4.0 * m2 * mf2 * (5.0 - 4.0 * t / mz2 + 2.0 * (t / mz2).powi(2));
+        // Vector-axial interference
+        let int = 4.0 * t * (t + 2.0 * u - 2.0 * (m2 + mf2));
+
+        8.0 * ncol
+            * ((gv * qv).powi(2) * vv
+                + (gv * qa).powi(2) * va
+                + (ga * qv).powi(2) * av
+                + (ga * qa).powi(2) * aa
+                + gv * ga * qv * qa * int)
+            / (t - mz2).powi(2)
+    }
+}
@@ -0,0 +1,140 @@
+use super::{couplings, ZPrimePortal};
+use crate::models::sm_fermions;
+use crate::utils::integration::*;
+use std::collections::BTreeMap;
+
+impl ZPrimePortal {
+    /// Compute the cross section for chi+chibar -> Z'^* -> f+fbar for a SM
+    /// fermion with mass `mf`, `ncol` colors and vector and axial couplings
+    /// `qv` and `qa`. The longitudinal part of the Z' propagator contributes
+    /// only when both the DM and the fermion have axial couplings.
+    pub fn sigma_xx_ff(&self, cme: f64, mf: f64, ncol: f64, qv: f64, qa: f64) -> f64 {
+        if cme > 2.0 * mf && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let mf2 = mf * mf;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_f = (1.0 - 4.0 * mf2 / s).sqrt();
+            let mz2 = self.mzp * self.mzp;
+            let prop = (s - mz2).powi(2) + mz2 * self.width_zp.powi(2);
+
+            let dm = self.gx_v.powi(2) * (1.0 + 2.0 * m2 / s) + self.gx_a.powi(2) * beta * beta;
+            let sm = qv * qv * (1.0 + 2.0 * mf2 / s) + qa * qa * beta_f * beta_f;
+            // Spin-averaged and angle-averaged squared matrix element
+            let msqrd = ncol
+                * (4.0 / 3.0 * s * s * dm * sm
+                    + 16.0 * (self.gx_a * qa).powi(2) * m2 * mf2 * (1.0 - s / mz2).powi(2));
+            msqrd * beta_f / (16.0 * std::f64::consts::PI * s * beta * prop)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the cross section for chi+chibar -> Z' + Z' through t- and
+    /// u-channel DM exchange.
+    pub fn sigma_xx_zpzp(&self, cme: f64) -> f64 {
+        if cme > 2.0 * self.mzp && cme > 2.0 * self.mx {
+            let s = cme * cme;
+            let m2 = self.mx * self.mx;
+            let v2 = self.mzp * self.mzp;
+            let beta = (1.0 - 4.0 * m2 / s).sqrt();
+            let beta_f = (1.0 - 4.0 * v2 / s).sqrt();
+            let e2 = s / 4.0;
+            // |p_i| |p_f|
+            let pp = e2 * beta * beta_f;
+
+            let mut sum = 0.0;
+            for (z, wgt) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+                let t = m2 + v2 - 2.0 * (e2 - pp * z);
+                let u = m2 + v2 - 2.0 * (e2 + pp * z);
+                sum += wgt * self.amplitude_sqrd_xx_zpzp(t, u);
+            }
+            // Average over initial spins and include symmetry factor of 1/2
+            // for the identical final states.
+            beta_f * sum / (256.0 * std::f64::consts::PI * s * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Squared matrix element for chi+chibar -> Z' + Z' summed over spins and
+    /// polarizations. The terms suppressed by powers of m_Z' arise from the
+    /// longitudinal Z' polarizations coupling to the axial current.
+    fn amplitude_sqrd_xx_zpzp(&self, t: f64, u: f64) -> f64 {
+        let m2 = self.mx * self.mx;
+        let v2 = self.mzp * self.mzp;
+        // The numerators are symmetric in t <-> u and t + u is fixed by s.
+        let tpu = t + u;
+        let tu = t * u;
+
+        let n_vv = -2.0 * tu * tu
+            + tu * (8.0 * m2 * m2 - 4.0 * m2 * tpu + 16.0 * m2 * v2 + tpu * tpu - 4.0 * tpu * v2
+                + 10.0 * v2 * v2)
+            - 6.0 * m2.powi(4)
+            + 6.0 * m2 * m2 * v2 * v2
+            - m2 * tpu.powi(3)
+            + tpu * tpu * (3.0 * m2 * m2 - v2 * v2)
+            - tpu * (4.0 * m2 * m2 * v2 + 6.0 * m2 * v2 * v2);
+        let n_va = -12.0 * v2 * tu * tu
+            + tu * (16.0 * m2.powi(3) - 16.0 * m2 * m2 * tpu - 16.0 * m2 * m2 * v2
+                + 4.0 * m2 * tpu * tpu
+                + 8.0 * m2 * tpu * v2
+                - 64.0 * m2 * v2 * v2
+                + 6.0 * tpu * tpu * v2
+                - 24.0 * tpu * v2 * v2
+                + 60.0 * v2.powi(3))
+            + 16.0 * m2.powi(5)
+            - 4.0 * m2.powi(4) * v2
+            - 48.0 * m2.powi(3) * v2 * v2
+            + 36.0 * m2 * m2 * v2.powi(3)
+            + tpu.powi(3) * (2.0 * m2 * v2 - 4.0 * m2 * m2)
+            + tpu
+                * tpu
+                * (20.0 * m2.powi(3) - 22.0 * m2 * m2 * v2 + 28.0 * m2 * v2 * v2
+                    - 6.0 * v2.powi(3))
+            + tpu
+                * (-32.0 * m2.powi(4) + 32.0 * m2.powi(3) * v2 + 24.0 * m2 * m2 * v2 * v2
+                    - 36.0 * m2 * v2.powi(3));
+        let n_aa = tu * tu * (8.0 * m2 * m2 - 4.0 * m2 * tpu - 8.0 * m2 * v2 - 2.0 * v2 * v2)
+            + tu * (-8.0 * m2.powi(3) * tpu
+                + 4.0 * m2 * m2 * tpu * tpu
+                + 56.0 * m2 * m2 * v2 * v2
+                + 4.0 * m2 * tpu * tpu * v2
+                - 4.0 * m2 * tpu * v2 * v2
+                - 32.0 * m2 * v2.powi(3)
+                + tpu * tpu * v2 * v2
+                - 4.0 * tpu * v2.powi(3)
+                + 10.0 * v2.powi(4))
+            - 8.0 * m2.powi(6)
+            + 8.0 * m2.powi(5) * v2
+            - 6.0 * m2.powi(4) * v2 * v2
+            + 6.0 * m2 * m2 * v2.powi(4)
+            - tpu.powi(3) * (4.0 * m2 * m2 * v2 + m2 * v2 * v2)
+            + tpu
+                * tpu
+                * (-4.0 * m2.powi(4) + 12.0 * m2.powi(3) * v2 - 9.0 * m2 * m2 * v2 * v2
+                    + 12.0 * m2 * v2.powi(3)
+                    - v2.powi(4))
+            + tpu
+                * (12.0 * m2.powi(5)
+                    - 16.0 * m2.powi(4) * v2
+                    - 4.0 * m2 * m2 * v2.powi(3)
+                    - 6.0 * m2 * v2.powi(4));
+
+        let (gv2, ga2) = (self.gx_v * self.gx_v, self.gx_a * self.gx_a);
+        8.0 * (gv2 * gv2 * n_vv + gv2 * ga2 * n_va / v2 + ga2 * ga2 * n_aa / (v2 * v2))
+            / ((t - m2) * (u - m2)).powi(2)
+    }
+    /// Compute the total annihilation cross section.
+    pub fn sigma_xx(&self, cme: f64) -> f64 {
+        self.sigma_xx_channels(cme).values().sum()
+    }
+    /// Compute the annihilation cross section into each final state.
+    pub fn sigma_xx_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        for f in sm_fermions().iter() {
+            let (qv, qa) = couplings(self.g_zp, self.charges, f);
+            channels.insert(f.name, self.sigma_xx_ff(cme, f.mass, f.ncol, qv, qa));
+        }
+        channels.insert("Z'Z'", self.sigma_xx_zpzp(cme));
+        channels
+    }
+}
@@ -0,0 +1,36 @@
+use super::{couplings, ZPrimeCharges, ZPrimePortal};
+use crate::models::sm_fermions;
+
+impl ZPrimePortal {
+    /// Compute the width of a vector with mass `mv` into a fermion pair with
+    /// mass `mf`, `ncol` colors and vector and axial couplings `gv` and `ga`.
+    pub(super) fn width_v_to_ff(mv: f64, gv: f64, ga: f64, mf: f64, ncol: f64) -> f64 {
+        if mv > 2.0 * mf {
+            let r = mf * mf / (mv * mv);
+            let beta = (1.0 - 4.0 * r).sqrt();
+            ncol * mv * beta / (12.0 * std::f64::consts::PI)
+                * (gv * gv * (1.0 + 2.0 * r) + ga * ga * beta * beta)
+        } else {
+            0.0
+        }
+    }
+    /// Compute the total width of the Z' into DM and SM fermions.
+    pub(super) fn compute_width_zp(
+        mx: f64,
+        mzp: f64,
+        gx_v: f64,
+        gx_a: f64,
+        g_zp: f64,
+        charges: ZPrimeCharges,
+    ) -> f64 {
+        let width_dm = ZPrimePortal::width_v_to_ff(mzp, gx_v, gx_a, mx, 1.0);
+        let width_sm: f64 = sm_fermions()
+            .iter()
+            .map(|f| {
+                let (qv, qa) = couplings(g_zp, charges, f);
+                ZPrimePortal::width_v_to_ff(mzp, qv, qa, f.mass, f.ncol)
+            })
+            .sum();
+        width_dm + width_sm
+    }
+}
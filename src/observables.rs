@@ -0,0 +1,216 @@
+//! Late-time annihilation observables. After computing the relic density,
+//! we need the annihilation cross section at small velocities:
+//!
+//! - today, in dwarf galaxies or the galactic center (v ~ 1e-3),
+//! - at recombination, which is constrained by the CMB through the
+//!   energy-injection parameter p_ann = f_eff <σv> / m.
+//!
+//! The thermal average is computed either for a Maxwellian velocity
+//! distribution with dispersion `v0`, f(v) ~ exp(-v^2 / v0^2), or for the
+//! phase-space distribution f(q) on the momentum grid returned by
+//! `integrate_full_boltzmann`.
+
+use crate::boltz::full::momentum_grid;
+use crate::boltz::traits::DarkMatterModel;
+use crate::constants::GEV2_TO_CM3_PER_S;
+use crate::utils::integration::*;
+use cyphus_integration::prelude::*;
+use ndarray::prelude::*;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::f64::consts::PI;
+
+/// Temperature of the photon bath at recombination (z = 1100) in GeV.
+pub const T_RECOMBINATION: f64 = 2.587e-10;
+
+/// Trait for models with DM annihilation cross sections. The total cross
+/// section is given by `DarkMatterModel::sigma`.
+pub trait Annihilation: DarkMatterModel {
+    /// Annihilation cross section into each final state given the
+    /// center-of-mass energy.
+    fn sigma_channels(&self, cme: f64) -> BTreeMap<&'static str, f64> {
+        let mut channels = BTreeMap::new();
+        channels.insert("total", self.sigma(cme));
+        channels
+    }
+}
+
+/// Velocity distribution of the DM used to compute thermal averages.
+pub enum VelocityDistribution<'a> {
+    /// Maxwellian with dispersion `v0`: f(v) ~ exp(-v^2 / v0^2).
+    Maxwellian(f64),
+    /// Phase-space distribution f(q) at x = m / T on the grid given by
+    /// `momentum_grid`.
+    PhaseSpace(f64, ArrayView1<'a, f64>),
+}
+
+/// Late-time annihilation observables.
+#[derive(Clone, Debug)]
+pub struct AnnihilationObservables {
+    /// <σv> today in cm^3/s.
+    pub sigmav_today: f64,
+    /// <σv> at recombination in cm^3/s.
+    pub sigmav_recombination: f64,
+    /// CMB energy-injection parameter p_ann in cm^3/s/GeV.
+    pub p_ann: f64,
+    /// <σv> today in cm^3/s for each final state.
+    pub sigmav_channels: BTreeMap<&'static str, f64>,
+    /// Branching fraction into each final state today.
+    pub branching_fractions: BTreeMap<&'static str, f64>,
+}
+
+/// Center-of-mass energy of two DM particles of mass `mx` with relative
+/// velocity `vrel`.
+pub fn cme_from_vrel(mx: f64, vrel: f64) -> f64 {
+    2.0 * mx / (1.0 - vrel * vrel / 4.0).sqrt()
+}
+
+/// Thermally average `sigma` (as a function of the center-of-mass energy)
+/// times the relative velocity over the distribution `dist`.
+pub fn thermal_average<F>(sigma: F, mx: f64, dist: &VelocityDistribution) -> f64
+where
+    F: Fn(f64) -> f64,
+{
+    match dist {
+        VelocityDistribution::Maxwellian(v0) => {
+            // The relative velocity is Maxwellian with dispersion sqrt(2) v0
+            let vr0 = std::f64::consts::SQRT_2 * v0;
+            let gk = GaussKronrodIntegratorBuilder::default()
+                .epsrel(1e-8)
+                .epsabs(0.0)
+                .key(2)
+                .build();
+            let f = |w: f64| -> f64 {
+                let vrel = vr0 * w;
+                w * w * (-w * w).exp() * vrel * sigma(cme_from_vrel(mx, vrel))
+            };
+            4.0 / PI.sqrt() * gk.integrate(f, 0.0, f64::INFINITY).val
+        }
+        VelocityDistribution::PhaseSpace(x, f) => {
+            let qs = momentum_grid(f.len());
+            let n = qs.len();
+            let mut num = 0.0;
+            let mut den = 0.0;
+            for i in 0..n {
+                let wi = if i == 0 || i == n - 1 { 0.5 } else { 1.0 };
+                let vi = qs[i] / x;
+                for k in 0..n {
+                    let wk = if k == 0 || k == n - 1 { 0.5 } else { 1.0 };
+                    let vk = qs[k] / x;
+                    let wgt = wi * wk * (qs[i] * qs[k]).powi(2) * f[i] * f[k];
+                    let mut sum = 0.0;
+                    for (theta, w) in (*GAUSS_LEG_NS).iter().zip((*GAUSS_LEG_WS).iter()) {
+                        let vrel = (vi * vi + vk * vk - 2.0 * vi * vk * theta).sqrt();
+                        sum += w * vrel * sigma(cme_from_vrel(mx, vrel));
+                    }
+                    num += wgt * sum / 2.0;
+                    den += wgt;
+                }
+            }
+            num / den
+        }
+    }
+}
+
+/// Thermally average σv for each final state of `model` over the
+/// distribution `dist`.
+pub fn thermal_average_channels<A: Annihilation>(
+    model: &A,
+    mx: f64,
+    dist: &VelocityDistribution,
+) -> BTreeMap<&'static str, f64> {
+    // Each channel is averaged separately, so cache the channels per
+    // center-of-mass energy to evaluate them only once.
+    let cache: RefCell<HashMap<u64, BTreeMap<&'static str, f64>>> = RefCell::new(HashMap::new());
+    let channel = |name: &'static str, cme: f64| -> f64 {
+        let mut cache = cache.borrow_mut();
+        let channels = cache
+            .entry(cme.to_bits())
+            .or_insert_with(|| model.sigma_channels(cme));
+        channels.get(name).copied().unwrap_or(0.0)
+    };
+    let names: Vec<&'static str> = model.sigma_channels(2.0 * mx).keys().copied().collect();
+    names
+        .into_iter()
+        .map(|name| (name, thermal_average(|cme| channel(name, cme), mx, dist)))
+        .collect()
+}
+
+/// Velocity dispersion of the DM at recombination given the kinetic
+/// decoupling temperature, using T_chi = T^2 / T_kd.
+pub fn recombination_dispersion(mx: f64, tkd: f64) -> f64 {
+    let tchi = T_RECOMBINATION * T_RECOMBINATION / tkd;
+    (2.0 * tchi / mx).sqrt()
+}
+
+/// Compute the late-time annihilation observables for DM of mass `mx`.
+/// Today's velocities are given by `today`, the velocity at recombination
+/// is fixed by the kinetic decoupling temperature `tkd` and `f_eff` is the
+/// efficiency of the energy deposition at recombination.
+pub fn annihilation_observables<A: Annihilation>(
+    model: &A,
+    mx: f64,
+    today: &VelocityDistribution,
+    tkd: f64,
+    f_eff: f64,
+) -> AnnihilationObservables {
+    let sv_today = thermal_average(|cme| model.sigma(cme), mx, today);
+    let rec = VelocityDistribution::Maxwellian(recombination_dispersion(mx, tkd));
+    let sv_rec = thermal_average(|cme| model.sigma(cme), mx, &rec);
+
+    let sv_channels = thermal_average_channels(model, mx, today);
+    let branching_fractions = sv_channels
+        .iter()
+        .map(|(name, sv)| (*name, if sv_today > 0.0 { sv / sv_today } else { 0.0 }))
+        .collect();
+
+    AnnihilationObservables {
+        sigmav_today: sv_today * GEV2_TO_CM3_PER_S,
+        sigmav_recombination: sv_rec * GEV2_TO_CM3_PER_S,
+        p_ann: f_eff * sv_rec * GEV2_TO_CM3_PER_S / mx,
+        sigmav_channels: sv_channels
+            .iter()
+            .map(|(name, sv)| (*name, sv * GEV2_TO_CM3_PER_S))
+            .collect(),
+        branching_fractions,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::models::ScalarSinglet;
+
+    /// A constant σv is unchanged by the thermal average.
+    #[test]
+    fn test_constant_sigmav() {
+        let (mx, sv) = (100.0, 1e-9);
+        let sigma = |cme: f64| {
+            let vrel = 2.0 * (1.0 - 4.0 * mx * mx / (cme * cme)).sqrt();
+            if vrel > 0.0 {
+                sv / vrel
+            } else {
+                0.0
+            }
+        };
+        let dist = VelocityDistribution::Maxwellian(1e-3);
+        let avg = thermal_average(sigma, mx, &dist);
+        assert!((avg / sv - 1.0).abs() < 1e-6, "Maxwellian: {:e}", avg);
+
+        let x = 100.0;
+        let f = momentum_grid(200).mapv(|q| (-q * q / 2.0).exp());
+        let dist = VelocityDistribution::PhaseSpace(x, f.view());
+        let avg = thermal_average(sigma, mx, &dist);
+        assert!((avg / sv - 1.0).abs() < 1e-6, "phase space: {:e}", avg);
+    }
+
+    #[test]
+    fn test_branching_fractions() {
+        let model = ScalarSinglet::new(200.0, 0.1);
+        let dist = VelocityDistribution::Maxwellian(1e-3);
+        let obs = annihilation_observables(&model, 200.0, &dist, 1e-2, 1.0);
+        let total: f64 = obs.branching_fractions.values().sum();
+        assert!(obs.branching_fractions.len() > 1);
+        assert!((total - 1.0).abs() < 1e-6, "branching fractions: {}", total);
+    }
+}
@@ -0,0 +1,242 @@
+//! Sommerfeld enhancement of the annihilation cross section due to the
+//! exchange of a light mediator of mass `mediator_mass` with coupling
+//! α between the incoming dark matter particles. The enhancement S(v)
+//! multiplies σv and depends on the velocity v of each DM particle in the
+//! center-of-mass frame. We provide three approximations:
+//!
+//! - `Coulomb`: massless mediator, S = (πα/v) / (1 - exp(-πα/v)),
+//! - `Hulthen`: analytic solution for the Hulthén potential, which closely
+//!   approximates the Yukawa potential,
+//! - `Yukawa`: numerical solution of the s-wave Schrödinger equation with
+//!   the Yukawa potential V(r) = -α exp(-m r) / r.
+//!
+//! Since the Yukawa factor is expensive, models tabulate it in v for their
+//! DM mass using `Sommerfeld::tabulate`.
+
+use crate::utils::interpolation::LogSpline;
+use std::f64::consts::PI;
+use std::sync::{Arc, Once};
+
+/// Maximum number of RK4 steps used to solve the Schrödinger equation.
+const MAX_YUKAWA_STEPS: usize = 10_000_000;
+/// Number of velocities in the tabulated Yukawa factor.
+const YUKAWA_TABLE_SIZE: usize = 400;
+
+/// Approximation used to compute the Sommerfeld factor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SommerfeldApprox {
+    Coulomb,
+    Hulthen,
+    Yukawa,
+}
+
+/// Yukawa Sommerfeld factor S(v) tabulated for a fixed DM mass.
+#[derive(Debug)]
+struct YukawaTable {
+    mx: f64,
+    spline: LogSpline,
+}
+
+/// Parameters of the potential generating the Sommerfeld enhancement.
+#[derive(Clone, Debug)]
+pub struct Sommerfeld {
+    /// Coupling of the mediator to the dark matter.
+    pub alpha: f64,
+    /// Mass of the mediator.
+    pub mediator_mass: f64,
+    /// Approximation used to compute the enhancement.
+    pub approx: SommerfeldApprox,
+    /// Tabulated Yukawa factor, if any.
+    table: Option<Arc<YukawaTable>>,
+}
+
+impl Sommerfeld {
+    pub fn new(alpha: f64, mediator_mass: f64, approx: SommerfeldApprox) -> Sommerfeld {
+        Sommerfeld {
+            alpha,
+            mediator_mass,
+            approx,
+            table: None,
+        }
+    }
+    /// Tabulate the Yukawa factor in v for DM of mass `mx`, so that `factor`
+    /// only needs to interpolate. Other approximations are cheap and are
+    /// returned unchanged.
+    pub fn tabulate(mut self, mx: f64) -> Sommerfeld {
+        if self.approx != SommerfeldApprox::Yukawa || self.alpha <= 0.0 {
+            return self;
+        }
+        let eps_phi = self.mediator_mass / (self.alpha * mx);
+        // S(v) saturates (or scales as a power law on resonance) once v is
+        // well below m_φ / m_χ, so the spline can be extrapolated below vmin.
+        let vmin = (1e-2 * self.alpha * eps_phi).min(1e-6);
+        let lvmin = vmin.ln();
+        let vs: Vec<f64> = (0..YUKAWA_TABLE_SIZE)
+            .map(|i| (lvmin * (1.0 - i as f64 / (YUKAWA_TABLE_SIZE - 1) as f64)).exp())
+            .collect();
+        let ss: Vec<f64> = vs
+            .iter()
+            .map(|&v| yukawa_or_hulthen(self.alpha, v, eps_phi))
+            .collect();
+        let spline = LogSpline::new(&vs, &ss).expect("Sommerfeld factors are positive and finite");
+        self.table = Some(Arc::new(YukawaTable { mx, spline }));
+        self
+    }
+    /// Compute the Sommerfeld factor for DM of mass `mx` where each DM
+    /// particle has velocity `v` in the center-of-mass frame.
+    pub fn factor(&self, mx: f64, v: f64) -> f64 {
+        if v <= 0.0 || self.alpha <= 0.0 {
+            return 1.0;
+        }
+        match self.approx {
+            SommerfeldApprox::Coulomb => sommerfeld_coulomb(self.alpha, v),
+            SommerfeldApprox::Hulthen => {
+                sommerfeld_hulthen(self.alpha, v, self.mediator_mass / (self.alpha * mx))
+            }
+            SommerfeldApprox::Yukawa => match &self.table {
+                Some(table) if table.mx == mx => table.spline.eval(v),
+                _ => yukawa_or_hulthen(self.alpha, v, self.mediator_mass / (self.alpha * mx)),
+            },
+        }
+    }
+    /// Compute the Sommerfeld factor for DM of mass `mx` given the
+    /// center-of-mass energy of the annihilating pair.
+    pub fn factor_cme(&self, mx: f64, cme: f64) -> f64 {
+        let v2 = 1.0 - 4.0 * mx * mx / (cme * cme);
+        if v2 > 0.0 {
+            self.factor(mx, v2.sqrt())
+        } else {
+            1.0
+        }
+    }
+}
+
+/// Sommerfeld factor for a massless mediator.
+pub fn sommerfeld_coulomb(alpha: f64, v: f64) -> f64 {
+    let z = PI * alpha / v;
+    z / (1.0 - (-z).exp())
+}
+
+/// Sommerfeld factor for the Hulthén potential. Here `eps_phi` is
+/// m_φ / (α m_χ).
+pub fn sommerfeld_hulthen(alpha: f64, v: f64, eps_phi: f64) -> f64 {
+    if eps_phi <= 0.0 {
+        return sommerfeld_coulomb(alpha, v);
+    }
+    let eps_v = v / alpha;
+    let eps = PI * PI * eps_phi / 6.0;
+    let y = 2.0 * PI * eps_v / eps;
+    let arg = 1.0 / eps - (eps_v / eps).powi(2);
+    // Write sinh(y) / (cosh(y) - c) in terms of exp(-y) to avoid overflow.
+    let ey = (-y).exp();
+    let cey = if arg >= 0.0 {
+        2.0 * (2.0 * PI * arg.sqrt()).cos() * ey
+    } else {
+        let w = 2.0 * PI * (-arg).sqrt();
+        (w - y).exp() + (-w - y).exp()
+    };
+    PI / eps_v * (1.0 - ey * ey) / (1.0 + ey * ey - cey)
+}
+
+/// Compute the Yukawa factor, falling back to the Hulthén approximation
+/// (with a warning) if the Schrödinger equation needs too many steps.
+fn yukawa_or_hulthen(alpha: f64, v: f64, eps_phi: f64) -> f64 {
+    match sommerfeld_yukawa(alpha, v, eps_phi) {
+        Some(s) if s.is_finite() => s,
+        _ => {
+            static WARN: Once = Once::new();
+            WARN.call_once(|| {
+                eprintln!(
+                    "warning: Yukawa Sommerfeld factor needs more than {} steps \
+                     (alpha = {:e}, v = {:e}, eps_phi = {:e}); using the Hulthén \
+                     approximation",
+                    MAX_YUKAWA_STEPS, alpha, v, eps_phi
+                );
+            });
+            sommerfeld_hulthen(alpha, v, eps_phi)
+        }
+    }
+}
+
+/// Sommerfeld factor for the Yukawa potential computed by numerically
+/// integrating the s-wave radial Schrödinger equation. Here `eps_phi` is
+/// m_φ / (α m_χ). Returns `None` if the integration needs more than
+/// `MAX_YUKAWA_STEPS` steps, which happens for very light mediators.
+///
+/// In terms of r' = α μ r, the radial equation reads
+///     u'' + (a^2 + 2 exp(-b r') / r') u = 0,
+/// with a = 2v / α and b = 2 eps_phi. Starting from u(0) = 0, u'(0) = 1, the
+/// Sommerfeld factor is 1 / (a A)^2 where A is the asymptotic amplitude of u.
+/// We integrate until the potential is negligible compared to a^2, after
+/// which A^2 = u^2 + u'^2 / a^2 is constant.
+pub fn sommerfeld_yukawa(alpha: f64, v: f64, eps_phi: f64) -> Option<f64> {
+    if eps_phi <= 0.0 {
+        return Some(sommerfeld_coulomb(alpha, v));
+    }
+    let a = 2.0 * v / alpha;
+    let b = 2.0 * eps_phi;
+    let a2 = a * a;
+
+    let h = 0.02 * (1.0 / a).min(1.0 / b).min(1.0);
+    // 2 exp(-b r) / r < 1e-12 a^2 for r > rmax
+    let rmax = (30.0 + (1.0 / a2).ln().max(0.0)) / b;
+    let nsteps = (rmax / h).ceil();
+    if nsteps > MAX_YUKAWA_STEPS as f64 {
+        return None;
+    }
+    let nsteps = nsteps as usize;
+
+    let rhs = |r: f64, u: f64| -> f64 { -(a2 + 2.0 * (-b * r).exp() / r) * u };
+
+    let mut r = h;
+    // u ~ r - r^2 near the origin
+    let mut u = h - h * h;
+    let mut du = 1.0 - 2.0 * h;
+    for _ in 0..nsteps {
+        let k1u = du;
+        let k1d = rhs(r, u);
+        let k2u = du + 0.5 * h * k1d;
+        let k2d = rhs(r + 0.5 * h, u + 0.5 * h * k1u);
+        let k3u = du + 0.5 * h * k2d;
+        let k3d = rhs(r + 0.5 * h, u + 0.5 * h * k2u);
+        let k4u = du + h * k3d;
+        let k4d = rhs(r + h, u + h * k3u);
+        u += h / 6.0 * (k1u + 2.0 * k2u + 2.0 * k3u + k4u);
+        du += h / 6.0 * (k1d + 2.0 * k2d + 2.0 * k3d + k4d);
+        r += h;
+    }
+    let amp2 = u * u + du * du / a2;
+    Some(1.0 / (a2 * amp2))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_coulomb_limit() {
+        let alpha = 0.01;
+        let v = 1e-2;
+        let sc = sommerfeld_coulomb(alpha, v);
+        let sh = sommerfeld_hulthen(alpha, v, 1e-8);
+        assert!(((sh - sc) / sc).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_yukawa_table() {
+        let (mx, alpha) = (1e3, 0.05);
+        let sf = Sommerfeld::new(alpha, 10.0, SommerfeldApprox::Yukawa);
+        let eps_phi = 10.0 / (alpha * mx);
+        let tab = sf.clone().tabulate(mx);
+        for &v in [3e-4, 2e-3, 0.03, 0.3].iter() {
+            let exact = sommerfeld_yukawa(alpha, v, eps_phi).unwrap();
+            assert!((tab.factor(mx, v) / exact - 1.0).abs() < 1e-3);
+            assert!((sf.factor(mx, v) / exact - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_yukawa_step_cap() {
+        assert!(sommerfeld_yukawa(0.01, 1e-3, 1e-9).is_none());
+    }
+}
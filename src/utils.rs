@@ -1,2 +1,3 @@
 pub mod derivatives;
 pub mod integration;
+pub mod interpolation;
@@ -0,0 +1,207 @@
+//! Interpolation of tabulated data using natural cubic splines. `LogSpline`
+//! interpolates in log-log space, which is appropriate for cross sections and
+//! rates spanning many orders of magnitude.
+
+use std::fmt;
+
+/// Reasons the data can't be interpolated.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InterpolationError {
+    /// The x and y data have different lengths.
+    LengthMismatch,
+    /// The data contains a NaN or infinite value.
+    NonFinite,
+    /// Two points share the same x but have different y.
+    DuplicateX,
+    /// Fewer than two distinct points remain.
+    TooFewPoints,
+}
+
+impl fmt::Display for InterpolationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let msg = match self {
+            InterpolationError::LengthMismatch => "x and y data have different lengths",
+            InterpolationError::NonFinite => "data contains non-finite values",
+            InterpolationError::DuplicateX => "data contains duplicate x with different y",
+            InterpolationError::TooFewPoints => "at least two distinct points are needed",
+        };
+        write!(f, "{}", msg)
+    }
+}
+
+impl std::error::Error for InterpolationError {}
+
+impl From<InterpolationError> for std::io::Error {
+    fn from(err: InterpolationError) -> std::io::Error {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Natural cubic spline through the points (xs, ys). Outside the range of the
+/// data the spline is extrapolated linearly.
+#[derive(Clone, Debug)]
+pub struct CubicSpline {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    /// Second derivatives at the knots.
+    m: Vec<f64>,
+}
+
+/// Cubic spline of ln(y) vs. ln(x). Only points with positive x and y are
+/// used.
+#[derive(Clone, Debug)]
+pub struct LogSpline {
+    spline: CubicSpline,
+}
+
+impl CubicSpline {
+    /// Construct the spline. The points are sorted by x and repeated points
+    /// are removed. The data must be finite, repeated x must have the same
+    /// y, and at least two distinct points are needed.
+    pub fn new(xs: &[f64], ys: &[f64]) -> Result<CubicSpline, InterpolationError> {
+        if xs.len() != ys.len() {
+            return Err(InterpolationError::LengthMismatch);
+        }
+        if xs.iter().chain(ys.iter()).any(|v| !v.is_finite()) {
+            return Err(InterpolationError::NonFinite);
+        }
+        let mut points: Vec<(f64, f64)> = xs.iter().copied().zip(ys.iter().copied()).collect();
+        // The data is finite, so the comparison can't fail
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        points.dedup();
+        if points.windows(2).any(|w| w[0].0 == w[1].0) {
+            return Err(InterpolationError::DuplicateX);
+        }
+        if points.len() < 2 {
+            return Err(InterpolationError::TooFewPoints);
+        }
+        let xs: Vec<f64> = points.iter().map(|p| p.0).collect();
+        let ys: Vec<f64> = points.iter().map(|p| p.1).collect();
+        let n = xs.len();
+        let mut m = vec![0.0; n];
+        if n > 2 {
+            // Solve the tridiagonal system for the second derivatives using
+            // the Thomas algorithm with m[0] = m[n-1] = 0.
+            let mut c = vec![0.0; n];
+            let mut d = vec![0.0; n];
+            for i in 1..n - 1 {
+                let h0 = xs[i] - xs[i - 1];
+                let h1 = xs[i + 1] - xs[i];
+                let rhs = 6.0 * ((ys[i + 1] - ys[i]) / h1 - (ys[i] - ys[i - 1]) / h0);
+                let diag = 2.0 * (h0 + h1) - h0 * c[i - 1];
+                c[i] = h1 / diag;
+                d[i] = (rhs - h0 * d[i - 1]) / diag;
+            }
+            for i in (1..n - 1).rev() {
+                m[i] = d[i] - c[i] * m[i + 1];
+            }
+        }
+        Ok(CubicSpline { xs, ys, m })
+    }
+    /// Evaluate the spline at `x`.
+    pub fn eval(&self, x: f64) -> f64 {
+        let n = self.xs.len();
+        if x < self.xs[0] {
+            let h = self.xs[1] - self.xs[0];
+            let slope = (self.ys[1] - self.ys[0]) / h - h * self.m[1] / 6.0;
+            return self.ys[0] + slope * (x - self.xs[0]);
+        }
+        if x > self.xs[n - 1] {
+            let h = self.xs[n - 1] - self.xs[n - 2];
+            let slope = (self.ys[n - 1] - self.ys[n - 2]) / h + h * self.m[n - 2] / 6.0;
+            return self.ys[n - 1] + slope * (x - self.xs[n - 1]);
+        }
+        let i = match self.xs.iter().position(|&xi| xi >= x) {
+            Some(0) => 1,
+            Some(i) => i,
+            None => n - 1,
+        };
+        let h = self.xs[i] - self.xs[i - 1];
+        let a = (self.xs[i] - x) / h;
+        let b = (x - self.xs[i - 1]) / h;
+        a * self.ys[i - 1]
+            + b * self.ys[i]
+            + ((a.powi(3) - a) * self.m[i - 1] + (b.powi(3) - b) * self.m[i]) * h * h / 6.0
+    }
+    /// Smallest x of the data.
+    pub fn xmin(&self) -> f64 {
+        self.xs[0]
+    }
+    /// Largest x of the data.
+    pub fn xmax(&self) -> f64 {
+        self.xs[self.xs.len() - 1]
+    }
+}
+
+impl LogSpline {
+    /// Construct the spline from the points with positive x and y. The same
+    /// requirements as for `CubicSpline::new` apply to the remaining points.
+    pub fn new(xs: &[f64], ys: &[f64]) -> Result<LogSpline, InterpolationError> {
+        if xs.len() != ys.len() {
+            return Err(InterpolationError::LengthMismatch);
+        }
+        if xs.iter().chain(ys.iter()).any(|v| !v.is_finite()) {
+            return Err(InterpolationError::NonFinite);
+        }
+        let (lxs, lys): (Vec<f64>, Vec<f64>) = xs
+            .iter()
+            .zip(ys.iter())
+            .filter(|(x, y)| **x > 0.0 && **y > 0.0)
+            .map(|(x, y)| (x.ln(), y.ln()))
+            .unzip();
+        Ok(LogSpline {
+            spline: CubicSpline::new(&lxs, &lys)?,
+        })
+    }
+    /// Evaluate the spline at `x`.
+    pub fn eval(&self, x: f64) -> f64 {
+        self.spline.eval(x.ln()).exp()
+    }
+    /// Smallest x of the data.
+    pub fn xmin(&self) -> f64 {
+        self.spline.xmin().exp()
+    }
+    /// Largest x of the data.
+    pub fn xmax(&self) -> f64 {
+        self.spline.xmax().exp()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_spline_power_law() {
+        let xs: Vec<f64> = (0..20).map(|i| 10f64.powf(0.3 * i as f64)).collect();
+        let ys: Vec<f64> = xs.iter().map(|x| 3.0 * x.powf(-1.5)).collect();
+        let spline = LogSpline::new(&xs, &ys).unwrap();
+        for &x in [2.0, 55.0, 1e4, 1e-2, 1e7].iter() {
+            let exact = 3.0 * f64::powf(x, -1.5);
+            assert!((spline.eval(x) / exact - 1.0).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_invalid_data() {
+        let err = |xs: &[f64], ys: &[f64]| LogSpline::new(xs, ys).err();
+        assert_eq!(
+            err(&[1.0, f64::NAN], &[1.0, 2.0]),
+            Some(InterpolationError::NonFinite)
+        );
+        assert_eq!(
+            err(&[1.0, 2.0, 2.0], &[1.0, 2.0, 3.0]),
+            Some(InterpolationError::DuplicateX)
+        );
+        assert_eq!(
+            err(&[-1.0, 0.0, 2.0], &[1.0, 2.0, 3.0]),
+            Some(InterpolationError::TooFewPoints)
+        );
+        assert_eq!(
+            err(&[1.0, 2.0], &[1.0]),
+            Some(InterpolationError::LengthMismatch)
+        );
+        // Repeated points are removed
+        assert!(err(&[1.0, 2.0, 2.0], &[1.0, 2.0, 2.0]).is_none());
+    }
+}